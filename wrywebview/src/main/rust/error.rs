@@ -6,12 +6,27 @@ pub enum WebViewError {
     #[error("unsupported platform for native webview")]
     UnsupportedPlatform,
 
-    #[error("invalid parent window handle")]
-    InvalidWindowHandle,
+    #[error("invalid parent window handle: {0}")]
+    InvalidWindowHandle(String),
 
     #[error("webview {0} not found")]
     WebViewNotFound(u64),
 
+    #[error("request {0} not found or already answered")]
+    RequestNotFound(u64),
+
+    #[error("no element matches selector {0}")]
+    ElementNotFound(String),
+
+    #[error("option {0} is not supported on this platform")]
+    UnsupportedOption(String),
+
+    #[error("invalid profile name {0:?}")]
+    InvalidProfile(String),
+
+    #[error("timed out waiting for {0}")]
+    Timeout(String),
+
     #[error("webview {0} must be accessed from the creating thread")]
     WrongThread(u64),
 
@@ -0,0 +1,298 @@
+//! Events emitted by WebViews and queued for the host.
+//!
+//! Events are produced either natively (engine signals) or by the injected
+//! scripts in [`crate::scripts`], which report back through the IPC channel
+//! using messages prefixed with [`BRIDGE_PREFIX`].
+
+use serde_json::Value;
+
+/// Prefix marking IPC messages sent by the library's own injected scripts.
+///
+/// Page scripts share the IPC channel and can post the same prefix, so bridge
+/// events (console, metadata, content size, scroll) are reports from untrusted
+/// content, not facts established by the engine.
+pub const BRIDGE_PREFIX: &str = "__wrywebview__:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ConsoleMessageLevel {
+    Debug,
+    Log,
+    Info,
+    Warning,
+    Error,
+}
+
+impl ConsoleMessageLevel {
+    fn from_name(name: &str) -> Self {
+        match name {
+            "debug" | "trace" => ConsoleMessageLevel::Debug,
+            "info" => ConsoleMessageLevel::Info,
+            "warn" => ConsoleMessageLevel::Warning,
+            "error" => ConsoleMessageLevel::Error,
+            _ => ConsoleMessageLevel::Log,
+        }
+    }
+}
+
+/// Page-declared metadata read from the document's `<head>`.
+#[derive(Debug, Clone, Default, PartialEq, uniffi::Record)]
+pub struct PageMetadata {
+    /// `<meta name="theme-color">`, honouring its `media` attribute.
+    pub theme_color: Option<String>,
+    /// `<meta name="description">`.
+    pub description: Option<String>,
+    /// Absolute URL of `<link rel="canonical">`.
+    pub canonical_url: Option<String>,
+    /// Absolute URL of `<link rel="manifest">`.
+    pub manifest_url: Option<String>,
+}
+
+/// Size of the document's content in logical (CSS) pixels; it can be smaller
+/// than the viewport.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Record)]
+pub struct ContentSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Scroll offset of the top-level document in logical (CSS) pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, uniffi::Record)]
+pub struct ScrollPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum FocusDirection {
+    Forward,
+    Backward,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum WebProcessTerminationReason {
+    Crashed,
+    ExceededMemoryLimit,
+    /// Ended through `terminate_web_process`.
+    TerminatedByApi,
+}
+
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum WebViewEvent {
+    /// A `console.*` call or an uncaught error in the page.
+    ConsoleMessage {
+        level: ConsoleMessageLevel,
+        message: String,
+        source_url: String,
+        line: u32,
+    },
+    /// Find-in-page located the query; `active_match` is 1-based.
+    FindTextFound {
+        query: String,
+        match_count: u32,
+        active_match: u32,
+    },
+    /// Find-in-page found no (further) match for the query.
+    FindTextNotFound { query: String },
+    /// The page's favicon changed; fetch it with `get_favicon`.
+    FaviconChanged,
+    /// The page's metadata changed (including on every new document).
+    PageMetadataChanged { metadata: PageMetadata },
+    /// The element under the mouse changed; all fields empty when leaving a link.
+    HoveredTargetChanged {
+        link_url: Option<String>,
+        link_title: Option<String>,
+        image_url: Option<String>,
+        is_editable: bool,
+    },
+    /// The document's scroll width or height changed.
+    ContentSizeChanged { size: ContentSize },
+    /// The document was scrolled; reported at most every 100 ms while scrolling.
+    ScrollChanged { position: ScrollPosition },
+    /// A custom context menu item was activated.
+    ContextMenuItemActivated { item_id: String },
+    /// The webview gained or lost keyboard focus.
+    FocusChanged { focused: bool },
+    /// Focus left the webview, by tabbing past the page's first or last focusable
+    /// element or through `release_focus`; the host should focus the next
+    /// component in `direction`.
+    FocusReleased { direction: FocusDirection },
+    /// The web process rendering the page exited and the view went blank;
+    /// `recovering` is true when auto-recover is reloading the last URL.
+    WebProcessTerminated {
+        reason: WebProcessTerminationReason,
+        recovering: bool,
+    },
+}
+
+/// Parses an IPC message sent by an injected script.
+///
+/// Returns `None` if the message is not a bridge message, in which case it
+/// belongs to the page and must be forwarded untouched.
+pub fn parse_bridge_message(message: &str) -> Option<Result<WebViewEvent, String>> {
+    let payload = message.strip_prefix(BRIDGE_PREFIX)?;
+    Some(parse_bridge_payload(payload))
+}
+
+fn parse_bridge_payload(payload: &str) -> Result<WebViewEvent, String> {
+    let value: Value =
+        serde_json::from_str(payload).map_err(|e| format!("invalid bridge payload: {e}"))?;
+    let kind = value
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "bridge payload without type".to_string())?;
+
+    match kind {
+        "console" => Ok(WebViewEvent::ConsoleMessage {
+            level: ConsoleMessageLevel::from_name(str_field(&value, "level")),
+            message: str_field(&value, "message").to_string(),
+            source_url: str_field(&value, "source").to_string(),
+            line: u32_field(&value, "line"),
+        }),
+        "metadata" => Ok(WebViewEvent::PageMetadataChanged {
+            metadata: PageMetadata {
+                theme_color: opt_str_field(&value, "themeColor"),
+                description: opt_str_field(&value, "description"),
+                canonical_url: opt_str_field(&value, "canonical"),
+                manifest_url: opt_str_field(&value, "manifest"),
+            },
+        }),
+        "contentSize" => Ok(WebViewEvent::ContentSizeChanged {
+            size: ContentSize {
+                width: u32_field(&value, "width"),
+                height: u32_field(&value, "height"),
+            },
+        }),
+        "scroll" => Ok(WebViewEvent::ScrollChanged {
+            position: ScrollPosition {
+                x: f64_field(&value, "x"),
+                y: f64_field(&value, "y"),
+            },
+        }),
+        other => Err(format!("unknown bridge message type: {other}")),
+    }
+}
+
+fn str_field<'a>(value: &'a Value, name: &str) -> &'a str {
+    value.get(name).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn opt_str_field(value: &Value, name: &str) -> Option<String> {
+    value
+        .get(name)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn f64_field(value: &Value, name: &str) -> f64 {
+    value.get(name).and_then(Value::as_f64).unwrap_or_default()
+}
+
+fn u32_field(value: &Value, name: &str) -> u32 {
+    value
+        .get(name)
+        .and_then(Value::as_u64)
+        .map(|v| v.min(u32::MAX as u64) as u32)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bridge(payload: &str) -> Result<WebViewEvent, String> {
+        parse_bridge_message(&format!("{BRIDGE_PREFIX}{payload}")).expect("bridge message")
+    }
+
+    #[test]
+    fn page_messages_are_not_bridge_messages() {
+        assert!(parse_bridge_message("hello").is_none());
+        assert!(parse_bridge_message("{\"type\":\"console\"}").is_none());
+    }
+
+    #[test]
+    fn parses_console_message() {
+        let event = bridge(
+            r#"{"type":"console","level":"warn","message":"careful","source":"https://a.test/x.js","line":12}"#,
+        )
+        .unwrap();
+        match event {
+            WebViewEvent::ConsoleMessage {
+                level,
+                message,
+                source_url,
+                line,
+            } => {
+                assert_eq!(level, ConsoleMessageLevel::Warning);
+                assert_eq!(message, "careful");
+                assert_eq!(source_url, "https://a.test/x.js");
+                assert_eq!(line, 12);
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+
+    #[test]
+    fn unknown_console_level_is_log() {
+        let event = bridge(r#"{"type":"console","level":"table","message":"m"}"#).unwrap();
+        assert!(matches!(
+            event,
+            WebViewEvent::ConsoleMessage {
+                level: ConsoleMessageLevel::Log,
+                line: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn parses_metadata_with_blank_fields_as_none() {
+        let event = bridge(
+            r#"{"type":"metadata","themeColor":" #fff ","description":"  ","canonical":"https://a.test/"}"#,
+        )
+        .unwrap();
+        match event {
+            WebViewEvent::PageMetadataChanged { metadata } => {
+                assert_eq!(metadata.theme_color.as_deref(), Some("#fff"));
+                assert_eq!(metadata.description, None);
+                assert_eq!(metadata.canonical_url.as_deref(), Some("https://a.test/"));
+                assert_eq!(metadata.manifest_url, None);
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+
+    #[test]
+    fn parses_content_size_saturating_at_u32() {
+        let size = bridge(r#"{"type":"contentSize","width":800,"height":5000000000}"#).unwrap();
+        assert!(matches!(
+            size,
+            WebViewEvent::ContentSizeChanged {
+                size: ContentSize {
+                    width: 800,
+                    height: u32::MAX
+                }
+            }
+        ));
+    }
+
+    #[test]
+    fn parses_scroll_position() {
+        let scroll = bridge(r#"{"type":"scroll","x":1.5,"y":40}"#).unwrap();
+        match scroll {
+            WebViewEvent::ScrollChanged { position } => {
+                assert_eq!(position.x, 1.5);
+                assert_eq!(position.y, 40.0);
+            }
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_payloads() {
+        assert!(bridge("not json").is_err());
+        assert!(bridge(r#"{"level":"log"}"#).is_err());
+        assert!(bridge(r#"{"type":"unknown"}"#).is_err());
+    }
+}
@@ -9,6 +9,9 @@ use std::os::raw::c_ulong;
 #[cfg(target_os = "linux")]
 use wry::raw_window_handle::XlibWindowHandle;
 
+#[cfg(target_os = "linux")]
+use crate::platform::linux::backend::Backend;
+
 #[cfg(target_os = "macos")]
 use wry::raw_window_handle::AppKitWindowHandle;
 
@@ -31,6 +34,18 @@ impl HasWindowHandle for RawWindow {
     }
 }
 
+/// The kind of native handle passed as a WebView parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ParentHandleKind {
+    /// The platform's window handle: HWND, NSView/NSWindow, or an X11 window.
+    Native,
+    /// A Wayland `wl_surface` (Linux).
+    WaylandSurface,
+    /// An empty window from `create_gtk_host_window`, which owns it on the
+    /// library's GTK thread (Linux). Other containers are rejected.
+    GtkContainer,
+}
+
 /// Creates a `Rect` with the given position and size, ensuring minimum dimensions.
 pub fn make_bounds(x: i32, y: i32, width: i32, height: i32) -> Rect {
     let width = width.max(1);
@@ -41,16 +56,18 @@ pub fn make_bounds(x: i32, y: i32, width: i32, height: i32) -> Rect {
     }
 }
 
-/// Converts a platform-specific handle to a `RawWindowHandle`.
+/// Converts a native platform handle to a `RawWindowHandle`.
 pub fn raw_window_handle_from(parent_handle: u64) -> Result<RawWindowHandle, WebViewError> {
     if parent_handle == 0 {
-        return Err(WebViewError::InvalidWindowHandle);
+        return Err(WebViewError::InvalidWindowHandle(
+            "handle is null".to_string(),
+        ));
     }
 
     #[cfg(target_os = "windows")]
     {
         let hwnd = NonZeroIsize::new(parent_handle as isize)
-            .ok_or(WebViewError::InvalidWindowHandle)?;
+            .ok_or_else(|| WebViewError::InvalidWindowHandle("HWND is null".to_string()))?;
         let handle = RawWindowHandle::Win32(Win32WindowHandle::new(hwnd));
         if log_enabled() {
             eprintln!("[wrywebview] raw_window_handle Win32=0x{:x}", parent_handle);
@@ -74,6 +91,15 @@ pub fn raw_window_handle_from(parent_handle: u64) -> Result<RawWindowHandle, Web
 
     #[cfg(target_os = "linux")]
     {
+        // wry can only reparent into X11 windows; under a Wayland GDK backend the
+        // XID would belong to XWayland, which GDK is not connected to.
+        if crate::platform::linux::backend::current() == Backend::Wayland {
+            return Err(WebViewError::InvalidWindowHandle(
+                "X11 window handle given but GDK is running on Wayland; \
+                 use a GtkContainer parent or set GDK_BACKEND=x11"
+                    .to_string(),
+            ));
+        }
         let handle = RawWindowHandle::Xlib(XlibWindowHandle::new(parent_handle as c_ulong));
         if log_enabled() {
             eprintln!("[wrywebview] raw_window_handle Xlib=0x{:x}", parent_handle);
@@ -4,8 +4,10 @@
 //! exposed through UniFFI for use from Kotlin/Swift.
 
 mod error;
+mod events;
 mod handle;
 mod platform;
+mod scripts;
 mod state;
 
 use std::str::FromStr;
@@ -19,6 +21,12 @@ use wry::http::{HeaderMap, HeaderValue};
 use wry::WebViewBuilder;
 
 pub use error::WebViewError;
+pub use events::{
+    ConsoleMessageLevel, ContentSize, FocusDirection, PageMetadata, ScrollPosition,
+    WebProcessTerminationReason, WebViewEvent,
+};
+
+pub use handle::ParentHandleKind;
 
 use handle::{make_bounds, raw_window_handle_from, RawWindow};
 use state::{get_state, register, unregister, with_webview, WebViewState};
@@ -177,50 +185,47 @@ pub trait NavigationHandler: Send + Sync {
     fn handle_navigation(&self, url: String) -> bool;
 }
 
-fn create_webview_inner(
-    parent_handle: u64,
-    width: i32,
-    height: i32,
-    url: String,
+fn normalize_user_agent(user_agent: Option<String>) -> Option<String> {
+    user_agent.and_then(|ua| {
+        let trimmed = ua.trim().to_string();
+        if trimmed.is_empty() { None } else { Some(trimmed) }
+    })
+}
+
+/// Creates a builder with the library's handlers and scripts attached.
+fn webview_builder<'a>(
+    url: &str,
     user_agent: Option<String>,
     nav_handler: Option<Box<dyn NavigationHandler>>,
-) -> Result<u64, WebViewError> {
-    let user_agent =
-        user_agent.and_then(|ua| {
-            let trimmed = ua.trim().to_string();
-            if trimmed.is_empty() { None } else { Some(trimmed) }
-        });
-
-    wry_log!(
-        "[wrywebview] create_webview handle=0x{:x} size={}x{} url={} user_agent={}",
-        parent_handle,
-        width,
-        height,
-        url,
-        user_agent.as_deref().unwrap_or("<default>")
-    );
+    state: &Arc<WebViewState>,
+) -> WebViewBuilder<'a> {
+    let state_for_nav = Arc::clone(state);
+    let state_for_load = Arc::clone(state);
+    let state_for_title = Arc::clone(state);
+    let state_for_ipc = Arc::clone(state);
+    let state_for_drop = Arc::clone(state);
 
-    let raw = raw_window_handle_from(parent_handle)?;
-    let window = RawWindow { raw };
+    let mut builder = WebViewBuilder::new()
+        .with_initialization_script(scripts::CONSOLE_CAPTURE)
+        .with_initialization_script(scripts::METADATA_OBSERVER)
+        .with_initialization_script(scripts::CONTENT_SIZE_OBSERVER);
 
+    // On Linux the scroll tracker is installed in an isolated world instead,
+    // and `register_webview` starts the first load once the engine is set up.
+    #[cfg(not(target_os = "linux"))]
+    {
+        builder = builder
+            .with_url(url)
+            .with_initialization_script(scripts::SCROLL_TRACKER);
+    }
     #[cfg(target_os = "linux")]
-    ensure_gtk_initialized()?;
-
-    let state = Arc::new(WebViewState::new(url.clone()));
-    let state_for_nav = Arc::clone(&state);
-    let state_for_load = Arc::clone(&state);
-    let state_for_title = Arc::clone(&state);
-    let state_for_ipc = Arc::clone(&state);
-
-    let mut builder = WebViewBuilder::new()
-        .with_url(&url)
-        .with_bounds(make_bounds(0, 0, width, height));
+    let _ = url;
 
     if let Some(ua) = user_agent {
         builder = builder.with_user_agent(ua);
     }
 
-    let webview = builder
+    builder
         .with_navigation_handler(move |new_url| {
             if let Some(handler) = &nav_handler {
                 return handler.handle_navigation(new_url.to_string());
@@ -239,6 +244,13 @@ fn create_webview_inner(
                 wry::PageLoadEvent::Started => {
                     wry_log!("[wrywebview] page_load_handler event=Started url={}", url);
                     state_for_load.is_loading.store(true, Ordering::SeqCst);
+                    // The new document reports its own metadata once parsed.
+                    let reset = state_for_load
+                        .update_page_metadata(PageMetadata::default())
+                        .and_then(|_| state_for_load.update_scroll_position(ScrollPosition::default()));
+                    if let Err(e) = reset {
+                        wry_log!("[wrywebview] page_load_handler state update failed: {}", e);
+                    }
                 }
                 wry::PageLoadEvent::Finished => {
                     wry_log!("[wrywebview] page_load_handler event=Finished url={}", url);
@@ -246,6 +258,7 @@ fn create_webview_inner(
                     if let Err(e) = state_for_load.update_current_url(url.clone()) {
                         wry_log!("[wrywebview] page_load_handler state update failed: {}", e);
                     }
+                    state_for_load.notify_load_finished();
                 }
             }
         })
@@ -255,67 +268,156 @@ fn create_webview_inner(
                 wry_log!("[wrywebview] title_changed state update failed: {}", e);
             }
         })
+        .with_drag_drop_handler(move |event| {
+            let Some(handler) = state_for_drop.handlers.drag_drop.get() else {
+                return false;
+            };
+            let Some(event) = DragDropEvent::from_wry(event) else {
+                return false;
+            };
+            wry_log!("[wrywebview] drag_drop event={:?}", event);
+            handler.handle_drag_drop(event)
+        })
         .with_ipc_handler(move |request| {
             let url = request.uri().to_string();
             let message = request.into_body();
             wry_log!("[wrywebview] ipc url={} body_len={}", url, message.len());
-            if let Err(e) = state_for_ipc.push_ipc_message(message) {
-                wry_log!("[wrywebview] ipc queue push failed: {}", e);
+            if message == scripts::automation::SELECTOR_MATCHED {
+                state_for_ipc.notify_page_changed();
+                return;
+            }
+            match events::parse_bridge_message(&message) {
+                Some(Ok(event)) => {
+                    let updated = match &event {
+                        WebViewEvent::PageMetadataChanged { metadata } => {
+                            state_for_ipc.update_page_metadata(metadata.clone())
+                        }
+                        WebViewEvent::ContentSizeChanged { size } => {
+                            state_for_ipc.update_content_size(*size)
+                        }
+                        WebViewEvent::ScrollChanged { position } => {
+                            state_for_ipc.update_scroll_position(*position)
+                        }
+                        _ => Ok(()),
+                    };
+                    if let Err(e) = updated {
+                        wry_log!("[wrywebview] event state update failed: {}", e);
+                    }
+                    if let Err(e) = state_for_ipc.push_event(event) {
+                        wry_log!("[wrywebview] event queue push failed: {}", e);
+                    }
+                }
+                Some(Err(e)) => {
+                    wry_log!("[wrywebview] ipc bridge message dropped: {}", e);
+                }
+                None => {
+                    if let Err(e) = state_for_ipc.push_ipc_message(message) {
+                        wry_log!("[wrywebview] ipc queue push failed: {}", e);
+                    }
+                }
             }
         })
+}
+
+fn create_webview_inner(
+    parent_handle: u64,
+    width: i32,
+    height: i32,
+    config: WebViewConfig,
+    nav_handler: Option<Box<dyn NavigationHandler>>,
+) -> Result<u64, WebViewError> {
+    let user_agent = normalize_user_agent(config.user_agent.clone());
+    let url = config.url.clone();
+
+    wry_log!(
+        "[wrywebview] create_webview handle=0x{:x} size={}x{} url={} user_agent={}",
+        parent_handle,
+        width,
+        height,
+        url,
+        user_agent.as_deref().unwrap_or("<default>")
+    );
+
+    // The GDK backend decides which handles are usable on Linux.
+    #[cfg(target_os = "linux")]
+    ensure_gtk_initialized()?;
+
+    let raw = raw_window_handle_from(parent_handle)?;
+    let window = RawWindow { raw };
+
+    let state = new_state(&config)?;
+    let webview = webview_builder(&url, user_agent, nav_handler, &state)
+        .with_bounds(make_bounds(0, 0, width, height))
         .build_as_child(&window)?;
 
-    // On Linux, set up focus handling for the GTK widget
+    #[cfg(target_os = "linux")]
+    let gtk_webview = webview.webview();
+
+    let id = register_webview(webview, Arc::clone(&state), &config)?;
+
     #[cfg(target_os = "linux")]
     {
-        use gdkx11::glib::translate::ToGlibPtr;
-        use gdkx11::glib::Cast;
-        use gdkx11::X11Display;
-        use gtk::prelude::WidgetExt;
-
-        let gtk_widget = webview.webview();
-        gtk_widget.set_can_focus(true);
-
-        // Connect to button-press-event to grab focus when clicked using X11
-        gtk_widget.connect_button_press_event(|widget, _event| {
-            wry_log!("[wrywebview] button_press_event -> grab_focus");
-
-            // Use X11 focus directly for proper keyboard input
-            if let Some(gdk_window) = widget.window() {
-                if let Some(display) = gdk::Display::default() {
-                    if let Ok(x11_display) = display.downcast::<X11Display>() {
-                        unsafe {
-                            let gdk_window_ptr: *mut gdk::ffi::GdkWindow = gdk_window.to_glib_none().0;
-                            let xid = gdkx11::ffi::gdk_x11_window_get_xid(
-                                gdk_window_ptr as *mut gdkx11::ffi::GdkX11Window,
-                            );
-
-                            if xid != 0 {
-                                let x11_display_ptr: *mut gdkx11::ffi::GdkX11Display = x11_display.to_glib_none().0;
-                                let x_display = gdkx11::ffi::gdk_x11_display_get_xdisplay(x11_display_ptr);
-
-                                if !x_display.is_null() {
-                                    x11::xlib::XSetInputFocus(
-                                        x_display as *mut x11::xlib::Display,
-                                        xid,
-                                        x11::xlib::RevertToParent,
-                                        x11::xlib::CurrentTime,
-                                    );
-                                    wry_log!("[wrywebview] button_press XSetInputFocus xid=0x{:x}", xid);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+        platform::linux::focus::connect_child_window(&gtk_webview, id, &state);
+        connect_click_to_focus(&gtk_webview, id);
+    }
+    Ok(id)
+}
 
-            widget.grab_focus();
-            gtk::glib::Propagation::Proceed
-        });
-        wry_log!("[wrywebview] gtk focus handling configured with X11 support");
+/// Creates the state of a WebView about to be built from `config`.
+fn new_state(config: &WebViewConfig) -> Result<Arc<WebViewState>, WebViewError> {
+    let mut state = WebViewState::new(config.url.clone());
+    if let Some(profile) = config.profile.clone() {
+        #[cfg(target_os = "linux")]
+        platform::linux::permission_policies::validate_profile(&profile)?;
+        state = state.with_profile(profile);
+    }
+    Ok(Arc::new(state))
+}
+
+/// Grabs keyboard focus when the page is clicked; an embedded webview does not
+/// receive the input focus on its own.
+#[cfg(target_os = "linux")]
+fn connect_click_to_focus(gtk_widget: &webkit2gtk::WebView, id: u64) {
+    use gtk::prelude::WidgetExt;
+
+    gtk_widget.set_can_focus(true);
+    gtk_widget.connect_button_press_event(move |widget, _event| {
+        wry_log!("[wrywebview] button_press_event -> grab_focus");
+        platform::linux::focus::grab(widget, id);
+        gtk::glib::Propagation::Proceed
+    });
+}
+
+/// Registers a freshly built WebView and wires up the engine signals.
+fn register_webview(
+    webview: wry::WebView,
+    state: Arc<WebViewState>,
+    config: &WebViewConfig,
+) -> Result<u64, WebViewError> {
+    #[cfg(target_os = "linux")]
+    let gtk_webview = webview.webview();
+
+    #[cfg(not(target_os = "linux"))]
+    let _ = config;
+
+    #[cfg(target_os = "linux")]
+    platform::linux::isolated_world::install(&gtk_webview, &[scripts::SCROLL_TRACKER]);
+
+    let id = register(webview, Arc::clone(&state))?;
+
+    #[cfg(target_os = "linux")]
+    platform::linux::rendering::apply(&gtk_webview, id, config.hardware_acceleration_policy);
+
+    #[cfg(target_os = "linux")]
+    platform::linux::connect_webview_signals(&gtk_webview, id, &state);
+
+    // Settings such as the favicon database are ignored once a load started.
+    #[cfg(target_os = "linux")]
+    {
+        use webkit2gtk::WebViewExt;
+        gtk_webview.load_uri(&config.url);
     }
 
-    let id = register(webview, state)?;
     wry_log!("[wrywebview] create_webview success id={}", id);
     Ok(id)
 }
@@ -331,12 +433,12 @@ pub fn create_webview(
     #[cfg(target_os = "linux")]
     {
         return run_on_gtk_thread(move || {
-            create_webview_inner(parent_handle, width, height, url, None, nav_handler)
+            create_webview_inner(parent_handle, width, height, WebViewConfig::new(url, None), nav_handler)
         });
     }
 
     #[cfg(not(target_os = "linux"))]
-    run_on_main_thread(move || create_webview_inner(parent_handle, width, height, url, None, nav_handler))
+    run_on_main_thread(move || create_webview_inner(parent_handle, width, height, WebViewConfig::new(url, None), nav_handler))
 }
 
 #[uniffi::export]
@@ -351,12 +453,218 @@ pub fn create_webview_with_user_agent(
     #[cfg(target_os = "linux")]
     {
         return run_on_gtk_thread(move || {
-            create_webview_inner(parent_handle, width, height, url, user_agent, nav_handler)
+            create_webview_inner(parent_handle, width, height, WebViewConfig::new(url, user_agent), nav_handler)
         });
     }
 
     #[cfg(not(target_os = "linux"))]
-    run_on_main_thread(move || create_webview_inner(parent_handle, width, height, url, user_agent,nav_handler))
+    run_on_main_thread(move || create_webview_inner(parent_handle, width, height, WebViewConfig::new(url, user_agent), nav_handler))
+}
+
+/// When the engine composites with the GPU (mapped to WebKitGTK settings on Linux).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum HardwareAccelerationPolicy {
+    Always,
+    Never,
+    OnDemand,
+}
+
+/// The renderer a WebView was created with, after applying its policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Renderer {
+    Hardware,
+    Software,
+}
+
+/// Settings for WebViews created through a config.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct WebViewConfig {
+    pub url: String,
+    pub user_agent: Option<String>,
+    /// `None` keeps the engine default. On Linux, GL failures always fall back
+    /// to software rendering.
+    pub hardware_acceleration_policy: Option<HardwareAccelerationPolicy>,
+    /// Profile whose persisted permission policies apply; `None` is `default`.
+    /// Names may contain ASCII letters, digits, `-`, `_` and `.`.
+    pub profile: Option<String>,
+}
+
+impl WebViewConfig {
+    fn new(url: String, user_agent: Option<String>) -> Self {
+        Self {
+            url,
+            user_agent,
+            hardware_acceleration_policy: None,
+            profile: None,
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn create_offscreen_webview_inner(
+    width: i32,
+    height: i32,
+    config: WebViewConfig,
+) -> Result<u64, WebViewError> {
+    let user_agent = normalize_user_agent(config.user_agent.clone());
+    wry_log!(
+        "[wrywebview] create_offscreen_webview size={}x{} url={} user_agent={}",
+        width,
+        height,
+        config.url,
+        user_agent.as_deref().unwrap_or("<default>")
+    );
+
+    ensure_gtk_initialized()?;
+
+    let state = new_state(&config)?;
+    let (webview, window) = platform::linux::offscreen::build(
+        webview_builder(&config.url, user_agent, None, &state),
+        width,
+        height,
+    )?;
+    let id = register_webview(webview, state, &config)?;
+    platform::linux::offscreen::adopt(id, window);
+    Ok(id)
+}
+
+/// Creates a WebView hosted in an unmapped GTK offscreen window, for tests and
+/// background rendering without visible UI (Linux only). It supports the same
+/// APIs as other WebViews; `set_bounds` resizes it.
+#[uniffi::export]
+pub fn create_offscreen_webview(
+    width: i32,
+    height: i32,
+    config: WebViewConfig,
+) -> Result<u64, WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || create_offscreen_webview_inner(width, height, config));
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (width, height, config);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+fn create_webview_with_parent_inner(
+    kind: ParentHandleKind,
+    parent_handle: u64,
+    width: i32,
+    height: i32,
+    config: WebViewConfig,
+    nav_handler: Option<Box<dyn NavigationHandler>>,
+) -> Result<u64, WebViewError> {
+    match kind {
+        ParentHandleKind::Native => {
+            create_webview_inner(parent_handle, width, height, config, nav_handler)
+        }
+        #[cfg(target_os = "linux")]
+        ParentHandleKind::WaylandSurface => {
+            ensure_gtk_initialized()?;
+            Err(platform::linux::backend::wayland_surface_error())
+        }
+        #[cfg(target_os = "linux")]
+        ParentHandleKind::GtkContainer => {
+            use gtk::prelude::WidgetExt;
+            use wry::WebViewBuilderExtUnix;
+
+            ensure_gtk_initialized()?;
+            let user_agent = normalize_user_agent(config.user_agent.clone());
+            wry_log!(
+                "[wrywebview] create_webview gtk_container=0x{:x} size={}x{} url={} backend={:?}",
+                parent_handle,
+                width,
+                height,
+                config.url,
+                platform::linux::backend::current()
+            );
+
+            let container = platform::linux::host_window::container(parent_handle)?;
+            let state = new_state(&config)?;
+            let webview = webview_builder(&config.url, user_agent, nav_handler, &state)
+                .build_gtk(&container)?;
+            let gtk_widget = webview.webview();
+            gtk_widget.set_size_request(width.max(1), height.max(1));
+            gtk_widget.show_all();
+            let id = register_webview(webview, state, &config)?;
+            platform::linux::host_window::adopt(parent_handle, id);
+            connect_click_to_focus(&gtk_widget, id);
+            Ok(id)
+        }
+        #[cfg(not(target_os = "linux"))]
+        ParentHandleKind::WaylandSurface | ParentHandleKind::GtkContainer => {
+            let _ = (parent_handle, width, height, config, nav_handler);
+            Err(WebViewError::UnsupportedPlatform)
+        }
+    }
+}
+
+/// Creates a WebView in a parent of the given handle kind. On Linux, X11
+/// windows need the X11 GDK backend; under Wayland, pass a window from
+/// [`create_gtk_host_window`] as a `GtkContainer`. `wl_surface` parents are
+/// rejected with an `InvalidWindowHandle` reason, since WebKitGTK cannot embed
+/// into them.
+#[uniffi::export]
+pub fn create_webview_with_parent(
+    kind: ParentHandleKind,
+    parent_handle: u64,
+    width: i32,
+    height: i32,
+    config: WebViewConfig,
+    nav_handler: Option<Box<dyn NavigationHandler>>,
+) -> Result<u64, WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || {
+        create_webview_with_parent_inner(kind, parent_handle, width, height, config, nav_handler)
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || {
+        create_webview_with_parent_inner(kind, parent_handle, width, height, config, nav_handler)
+    });
+    result
+}
+
+/// Creates a GTK toplevel window on the library's GTK thread and returns its
+/// `GtkWindow*`, to pass to [`create_webview_with_parent`] as a `GtkContainer`
+/// (Linux only). This is how WebViews are shown under Wayland. The window holds
+/// one WebView; closing it, by the user or [`destroy_gtk_host_window`],
+/// destroys that WebView too.
+#[uniffi::export]
+pub fn create_gtk_host_window(width: i32, height: i32, title: String) -> Result<u64, WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || {
+        ensure_gtk_initialized()?;
+        let handle = platform::linux::host_window::create(width, height, &title);
+        wry_log!(
+            "[wrywebview] create_gtk_host_window handle=0x{:x} size={}x{}",
+            handle,
+            width,
+            height
+        );
+        Ok(handle)
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (width, height, title);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Closes a window from [`create_gtk_host_window`] and destroys its WebView.
+#[uniffi::export]
+pub fn destroy_gtk_host_window(handle: u64) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] destroy_gtk_host_window handle=0x{:x}", handle);
+
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || platform::linux::host_window::destroy(handle));
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = handle;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
 }
 
 // ============================================================================
@@ -371,7 +679,13 @@ fn set_bounds_inner(id: u64, x: i32, y: i32, width: i32, height: i32) -> Result<
         );
     }
     let bounds = make_bounds(x, y, width, height);
-    with_webview(id, |webview| webview.set_bounds(bounds).map_err(WebViewError::from))
+    with_webview(id, |webview| {
+        webview.set_bounds(bounds).map_err(WebViewError::from)?;
+        // wry does not size WebViews without a native parent.
+        #[cfg(target_os = "linux")]
+        platform::linux::offscreen::resize(id, &webview.webview(), width, height);
+        Ok(())
+    })
 }
 
 #[uniffi::export]
@@ -398,6 +712,39 @@ pub fn set_bounds(id: u64, x: i32, y: i32, width: i32, height: i32) -> Result<()
     }
 }
 
+// ============================================================================
+// Visibility
+// ============================================================================
+
+fn set_visible_inner(id: u64, visible: bool) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] set_visible id={} visible={}", id, visible);
+    with_webview(id, |webview| webview.set_visible(visible).map_err(WebViewError::from))?;
+    get_state(id)?.is_visible.store(visible, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Hides or shows the WebView without destroying it. A hidden page keeps its
+/// DOM and JavaScript state; the engine stops painting it and reports
+/// `document.hidden`, which pauses `requestAnimationFrame`.
+///
+/// Timers are not throttled: WebKitGTK offers no control over timer
+/// throttling, so hidden pages keep running `setTimeout` and `setInterval`
+/// callbacks. Pages can stop their own work on `visibilitychange`.
+#[uniffi::export]
+pub fn set_visible(id: u64, visible: bool) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || set_visible_inner(id, visible));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || set_visible_inner(id, visible));
+    result
+}
+
+/// Returns whether the WebView is shown, as last set by `set_visible`.
+#[uniffi::export]
+pub fn is_visible(id: u64) -> Result<bool, WebViewError> {
+    Ok(get_state(id)?.is_visible.load(Ordering::SeqCst))
+}
+
 // ============================================================================
 // Navigation
 // ============================================================================
@@ -505,6 +852,14 @@ pub trait JavaScriptCallback: Send + Sync {
     fn on_result(&self, result: String);
 }
 
+/// Completion of an asynchronous operation without a result value.
+#[uniffi::export(callback_interface)]
+pub trait CompletionCallback: Send + Sync {
+    fn on_finished(&self);
+
+    fn on_error(&self, message: String);
+}
+
 fn evaluate_javascript_inner(
     id: u64,
     script: String,
@@ -602,6 +957,55 @@ pub fn reload(id: u64) -> Result<(), WebViewError> {
     run_on_main_thread(move || reload_inner(id))
 }
 
+// ============================================================================
+// Web Process
+// ============================================================================
+
+/// Opts in to reloading the last URL when the web process crashes or is killed
+/// (Linux only). Termination is reported as `WebProcessTerminated` either way.
+#[uniffi::export]
+pub fn set_auto_recover(id: u64, enabled: bool) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = {
+        get_state(id)?.auto_recover.store(enabled, Ordering::SeqCst);
+        Ok(())
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, enabled);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+fn terminate_web_process_inner(id: u64) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] terminate_web_process id={}", id);
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::process::terminate(&webview.webview());
+        Ok(())
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = id;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Kills the WebView's web process to simulate a crash in tests (Linux only).
+#[uniffi::export]
+pub fn terminate_web_process(id: u64) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || terminate_web_process_inner(id));
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = id;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
 // ============================================================================
 // Focus
 // ============================================================================
@@ -609,58 +1013,10 @@ pub fn reload(id: u64) -> Result<(), WebViewError> {
 fn focus_inner(id: u64) -> Result<(), WebViewError> {
     wry_log!("[wrywebview] focus id={}", id);
     with_webview(id, |webview| {
-        // On Linux, we need to use X11 focus directly since the GTK widget
-        // is embedded in a foreign (AWT/Swing) window hierarchy
+        // Embedded in a foreign X11 window, GTK alone cannot take the input focus.
         #[cfg(target_os = "linux")]
         {
-            use gdkx11::glib::translate::ToGlibPtr;
-            use gdkx11::glib::Cast;
-            use gdkx11::X11Display;
-            use gtk::prelude::WidgetExt;
-
-            let gtk_widget = webview.webview();
-            gtk_widget.set_can_focus(true);
-
-            // First, ensure the widget is realized and has a window
-            if !gtk_widget.is_realized() {
-                gtk_widget.realize();
-            }
-
-            // Get the GDK window and use X11 to set focus
-            if let Some(gdk_window) = gtk_widget.window() {
-                // Get the X11 display from GDK
-                if let Some(display) = gdk::Display::default() {
-                    if let Ok(x11_display) = display.downcast::<X11Display>() {
-                        unsafe {
-                            // Get the X11 window ID (XID) from the GDK window
-                            let gdk_window_ptr: *mut gdk::ffi::GdkWindow = gdk_window.to_glib_none().0;
-                            let xid = gdkx11::ffi::gdk_x11_window_get_xid(
-                                gdk_window_ptr as *mut gdkx11::ffi::GdkX11Window,
-                            );
-
-                            if xid != 0 {
-                                // Get the raw X11 display pointer
-                                let x11_display_ptr: *mut gdkx11::ffi::GdkX11Display = x11_display.to_glib_none().0;
-                                let x_display = gdkx11::ffi::gdk_x11_display_get_xdisplay(x11_display_ptr);
-
-                                if !x_display.is_null() {
-                                    // XSetInputFocus: RevertToParent = 2, CurrentTime = 0
-                                    x11::xlib::XSetInputFocus(
-                                        x_display as *mut x11::xlib::Display,
-                                        xid,
-                                        x11::xlib::RevertToParent,
-                                        x11::xlib::CurrentTime,
-                                    );
-                                    wry_log!("[wrywebview] XSetInputFocus xid=0x{:x}", xid);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-
-            // Also call GTK grab_focus as a fallback
-            gtk_widget.grab_focus();
+            platform::linux::focus::grab(&webview.webview(), id);
             wry_log!("[wrywebview] gtk grab_focus called");
         }
 
@@ -681,6 +1037,37 @@ pub fn focus(id: u64) -> Result<(), WebViewError> {
     run_on_main_thread(move || focus_inner(id))
 }
 
+fn release_focus_inner(id: u64, direction: FocusDirection) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] release_focus id={} direction={:?}", id, direction);
+    with_webview(id, |webview| {
+        webview
+            .evaluate_script("if (document.activeElement) { document.activeElement.blur(); }")
+            .map_err(WebViewError::from)?;
+
+        // On Linux the X11 input focus goes back to the embedding window.
+        #[cfg(target_os = "linux")]
+        let result = {
+            platform::linux::focus::release(&webview.webview(), id);
+            Ok(())
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = webview.focus_parent().map_err(WebViewError::from);
+        result
+    })?;
+    get_state(id)?.push_event(WebViewEvent::FocusReleased { direction })
+}
+
+/// Gives keyboard focus back to the host window and queues a `FocusReleased`
+/// event so the host can move focus on in `direction`.
+#[uniffi::export]
+pub fn release_focus(id: u64, direction: FocusDirection) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || release_focus_inner(id, direction));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || release_focus_inner(id, direction));
+    result
+}
+
 // ============================================================================
 // State Queries
 // ============================================================================
@@ -711,16 +1098,92 @@ pub fn get_title(id: u64) -> Result<String, WebViewError> {
     Ok(title.clone())
 }
 
+/// Returns the metadata declared by the current page.
 #[uniffi::export]
-pub fn can_go_back(id: u64) -> Result<bool, WebViewError> {
+pub fn get_page_metadata(id: u64) -> Result<PageMetadata, WebViewError> {
     let state = get_state(id)?;
-    state.can_go_back()
+    let metadata = state
+        .page_metadata
+        .lock()
+        .map_err(|_| WebViewError::Internal("metadata lock poisoned".to_string()))?;
+    Ok(metadata.clone())
 }
 
+/// Returns the document's last reported content size in logical pixels.
 #[uniffi::export]
-pub fn can_go_forward(id: u64) -> Result<bool, WebViewError> {
+pub fn get_content_size(id: u64) -> Result<ContentSize, WebViewError> {
     let state = get_state(id)?;
-    state.can_go_forward()
+    let size = state
+        .content_size
+        .lock()
+        .map_err(|_| WebViewError::Internal("content size lock poisoned".to_string()))?;
+    Ok(*size)
+}
+
+fn get_renderer_inner(id: u64) -> Result<Renderer, WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = {
+        get_state(id)?;
+        Ok(platform::linux::rendering::renderer(id))
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = id;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Reports whether the WebView renders with the GPU or fell back to software
+/// (Linux only). `Hardware` means WebKit may composite with GL: GL works, the
+/// policy is not `Never` and compositing is not disabled by the environment.
+/// With `OnDemand` or the engine default, pages that need compositing are
+/// composited on the GPU and the rest are painted directly.
+#[uniffi::export]
+pub fn get_renderer(id: u64) -> Result<Renderer, WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || get_renderer_inner(id));
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = id;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+fn get_favicon_inner(id: u64) -> Result<Option<Vec<u8>>, WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::favicon::favicon_png(&webview.webview())
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = id;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Returns the current page's favicon as PNG bytes, if it has one.
+#[uniffi::export]
+pub fn get_favicon(id: u64) -> Result<Option<Vec<u8>>, WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || get_favicon_inner(id));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || get_favicon_inner(id));
+    result
+}
+
+#[uniffi::export]
+pub fn can_go_back(id: u64) -> Result<bool, WebViewError> {
+    let state = get_state(id)?;
+    state.can_go_back()
+}
+
+#[uniffi::export]
+pub fn can_go_forward(id: u64) -> Result<bool, WebViewError> {
+    let state = get_state(id)?;
+    state.can_go_forward()
 }
 
 #[uniffi::export]
@@ -729,6 +1192,13 @@ pub fn drain_ipc_messages(id: u64) -> Result<Vec<String>, WebViewError> {
     state.drain_ipc_messages()
 }
 
+/// Returns and clears the events queued since the last call.
+#[uniffi::export]
+pub fn drain_events(id: u64) -> Result<Vec<WebViewEvent>, WebViewError> {
+    let state = get_state(id)?;
+    state.drain_events()
+}
+
 // ============================================================================
 // Cookies
 // ============================================================================
@@ -818,12 +1288,1429 @@ pub fn set_cookie(id: u64, cookie: WebViewCookie) -> Result<(), WebViewError> {
 }
 
 // ============================================================================
-// Destruction
+// Script Dialogs
 // ============================================================================
 
-fn destroy_webview_inner(id: u64) -> Result<(), WebViewError> {
-    wry_log!("[wrywebview] destroy_webview id={}", id);
-    unregister(id)
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum ScriptDialogKind {
+    Alert,
+    Confirm,
+    Prompt,
+    BeforeUnloadConfirm,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ScriptDialogRequest {
+    /// Identifies the dialog in `respond_to_script_dialog`.
+    pub request_id: u64,
+    pub kind: ScriptDialogKind,
+    pub message: String,
+    /// Initial text of a `prompt()` dialog.
+    pub default_text: Option<String>,
+}
+
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum ScriptDialogResponse {
+    /// OK; a prompt returns its default text.
+    Accept,
+    /// OK with the given prompt text.
+    AcceptWithText { text: String },
+    Cancel,
+}
+
+#[uniffi::export(callback_interface)]
+pub trait ScriptDialogHandler: Send + Sync {
+    /// Called on the GTK thread. Return true to answer later through
+    /// `respond_to_script_dialog`, false to show the engine's default dialog.
+    fn handle_script_dialog(&self, request: ScriptDialogRequest) -> bool;
+}
+
+/// Routes the page's JavaScript dialogs to `handler` (Linux only).
+#[uniffi::export]
+pub fn set_script_dialog_handler(
+    id: u64,
+    handler: Option<Box<dyn ScriptDialogHandler>>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = {
+        let state = get_state(id)?;
+        state.handlers.script_dialog.set(handler.map(Arc::from))
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, handler);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+#[uniffi::export]
+pub fn respond_to_script_dialog(
+    id: u64,
+    request_id: u64,
+    response: ScriptDialogResponse,
+) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] respond_to_script_dialog id={} request={}", id, request_id);
+    #[cfg(target_os = "linux")]
+    let result =
+        run_on_gtk_thread(move || platform::linux::dialogs::respond(id, request_id, response));
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, request_id, response);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+// ============================================================================
+// Permissions
+// ============================================================================
+
+/// Kind of permission requested by a page.
+///
+/// Clipboard access is not reported by WebKitGTK as a permission request; it
+/// is governed by the engine's clipboard settings instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum PermissionKind {
+    Geolocation,
+    Camera,
+    Microphone,
+    Notifications,
+    DeviceInfo,
+    MediaKeySystem,
+    PointerLock,
+    WebsiteDataAccess,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum PermissionDecision {
+    Allow,
+    Deny,
+    /// Defers the decision: the handler answers later through
+    /// `respond_to_permission_request`. As a policy, removes a stored decision.
+    AskLater,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct PermissionRequestInfo {
+    /// Identifies the request in `respond_to_permission_request`.
+    pub request_id: u64,
+    /// Origin asking for the permission, e.g. `https://example.com`. Requests
+    /// carry the top-level origin, which delegates them to its frames, except
+    /// `WebsiteDataAccess`: WebKitGTK only reports the requesting frame's
+    /// domain (`example.com`, no scheme or port), which is used instead.
+    pub origin: String,
+    /// Origin of the top-level document.
+    pub top_level_origin: String,
+    /// Requested permissions; camera and microphone may be requested together.
+    pub kinds: Vec<PermissionKind>,
+}
+
+#[uniffi::export(callback_interface)]
+pub trait PermissionHandler: Send + Sync {
+    /// Called on the GTK thread for requests without a stored policy.
+    fn handle_permission_request(&self, request: PermissionRequestInfo) -> PermissionDecision;
+}
+
+/// Routes the page's permission requests to `handler` (Linux only).
+#[uniffi::export]
+pub fn set_permission_handler(
+    id: u64,
+    handler: Option<Box<dyn PermissionHandler>>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = {
+        let state = get_state(id)?;
+        state.handlers.permission.set(handler.map(Arc::from))
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, handler);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+#[uniffi::export]
+pub fn respond_to_permission_request(
+    id: u64,
+    request_id: u64,
+    allow: bool,
+) -> Result<(), WebViewError> {
+    wry_log!(
+        "[wrywebview] respond_to_permission_request id={} request={} allow={}",
+        id,
+        request_id,
+        allow
+    );
+    #[cfg(target_os = "linux")]
+    let result =
+        run_on_gtk_thread(move || platform::linux::permissions::respond(id, request_id, allow));
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, request_id, allow);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Stores a decision for `origin` applied by every WebView of `profile` before
+/// asking the handler, and persists it in the profile's data directory.
+/// `origin` may be `*` to match any origin; `AskLater` clears it.
+/// `WebsiteDataAccess` policies are keyed by the requesting domain, e.g.
+/// `example.com`, as reported in `PermissionRequestInfo::origin`.
+#[uniffi::export]
+pub fn set_permission_policy(
+    profile: String,
+    origin: String,
+    kind: PermissionKind,
+    decision: PermissionDecision,
+) -> Result<(), WebViewError> {
+    wry_log!(
+        "[wrywebview] set_permission_policy profile={} origin={} kind={:?} decision={:?}",
+        profile,
+        origin,
+        kind,
+        decision
+    );
+    #[cfg(target_os = "linux")]
+    let result = platform::linux::permissions::set_policy(&profile, &origin, kind, decision);
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (profile, origin, kind, decision);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+// ============================================================================
+// File Chooser
+// ============================================================================
+
+/// An `<input type=file>` activation.
+///
+/// WebKitGTK does not support directory uploads (`webkitdirectory`), so the
+/// host is always asked for files.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FileChooserRequestInfo {
+    /// Identifies the request in `respond_to_file_chooser`.
+    pub request_id: u64,
+    /// MIME types from the input's `accept` attribute; empty accepts anything.
+    pub mime_types: Vec<String>,
+    pub select_multiple: bool,
+    /// Files selected by a previous activation of the same input.
+    pub selected_files: Vec<String>,
+}
+
+#[uniffi::export(callback_interface)]
+pub trait FileChooserHandler: Send + Sync {
+    /// Called on the GTK thread. Return true to answer later through
+    /// `respond_to_file_chooser`, false to show the engine's default chooser.
+    fn handle_file_chooser(&self, request: FileChooserRequestInfo) -> bool;
+}
+
+/// Routes `<input type=file>` choosers to `handler` (Linux only).
+#[uniffi::export]
+pub fn set_file_chooser_handler(
+    id: u64,
+    handler: Option<Box<dyn FileChooserHandler>>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = {
+        let state = get_state(id)?;
+        state.handlers.file_chooser.set(handler.map(Arc::from))
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, handler);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Completes a file chooser with absolute `paths`; `None` or an empty list cancels.
+#[uniffi::export]
+pub fn respond_to_file_chooser(
+    id: u64,
+    request_id: u64,
+    paths: Option<Vec<String>>,
+) -> Result<(), WebViewError> {
+    wry_log!(
+        "[wrywebview] respond_to_file_chooser id={} request={} paths={:?}",
+        id,
+        request_id,
+        paths
+    );
+    #[cfg(target_os = "linux")]
+    let result =
+        run_on_gtk_thread(move || platform::linux::file_chooser::respond(id, request_id, paths));
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, request_id, paths);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+// ============================================================================
+// Fullscreen
+// ============================================================================
+
+#[uniffi::export(callback_interface)]
+pub trait FullscreenHandler: Send + Sync {
+    /// Called on the GTK thread when the page requests fullscreen. Return true
+    /// to accept (and expand the webview with `set_bounds`), false to refuse.
+    fn handle_enter_fullscreen(&self) -> bool;
+
+    /// Called on the GTK thread once the page has left fullscreen.
+    fn handle_leave_fullscreen(&self);
+}
+
+/// Routes the page's fullscreen requests to `handler` (Linux only).
+#[uniffi::export]
+pub fn set_fullscreen_handler(
+    id: u64,
+    handler: Option<Box<dyn FullscreenHandler>>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = {
+        let state = get_state(id)?;
+        state.handlers.fullscreen.set(handler.map(Arc::from))
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, handler);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+fn exit_fullscreen_inner(id: u64) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] exit_fullscreen id={}", id);
+    with_webview(id, |webview| {
+        webview
+            .evaluate_script(
+                "if (document.fullscreenElement || document.webkitFullscreenElement) { \
+                 (document.exitFullscreen || document.webkitExitFullscreen).call(document); }",
+            )
+            .map_err(WebViewError::from)
+    })
+}
+
+#[uniffi::export]
+pub fn exit_fullscreen(id: u64) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || exit_fullscreen_inner(id));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || exit_fullscreen_inner(id));
+    result
+}
+
+// ============================================================================
+// Context Menu
+// ============================================================================
+
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum ContextMenuItem {
+    /// A WebKit item, identified by its action name. Stock items use the name
+    /// of WebKit's `WebKitContextMenuAction` in CamelCase without prefix
+    /// (e.g. `Reload`, `InspectElement`, `OpenLinkInNewWindow`, `NoAction`);
+    /// other items are `Custom:<GAction name>`, or `Custom:<position>` when
+    /// they have no action. Only items offered in `ContextMenuInfo::items`
+    /// can be shown.
+    Builtin { action: String },
+    /// A host item; activating it queues a `ContextMenuItemActivated` event.
+    Custom {
+        id: String,
+        label: String,
+        enabled: bool,
+    },
+    Separator,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct ContextMenuInfo {
+    pub link_url: Option<String>,
+    pub image_url: Option<String>,
+    pub media_url: Option<String>,
+    pub has_selection: bool,
+    pub is_editable: bool,
+    /// Position relative to the webview in logical pixels (0 for keyboard menus).
+    pub x: f64,
+    pub y: f64,
+    /// The items WebKit would show.
+    pub items: Vec<ContextMenuItem>,
+}
+
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum ContextMenuResponse {
+    ShowDefault,
+    /// Shows `items` instead of the default ones.
+    Show { items: Vec<ContextMenuItem> },
+    /// Shows nothing, e.g. when the host displays its own menu.
+    Suppress,
+}
+
+#[uniffi::export(callback_interface)]
+pub trait ContextMenuHandler: Send + Sync {
+    /// Called on the GTK thread before the native context menu is shown.
+    fn handle_context_menu(&self, info: ContextMenuInfo) -> ContextMenuResponse;
+}
+
+/// Routes context menus to `handler` (Linux only).
+#[uniffi::export]
+pub fn set_context_menu_handler(
+    id: u64,
+    handler: Option<Box<dyn ContextMenuHandler>>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = {
+        let state = get_state(id)?;
+        state.handlers.context_menu.set(handler.map(Arc::from))
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, handler);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+// ============================================================================
+// Drag and Drop
+// ============================================================================
+
+/// A file drag over the webview; positions are relative to its top-left
+/// corner, in logical pixels on Linux and macOS.
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum DragDropEvent {
+    Enter { paths: Vec<String>, x: i32, y: i32 },
+    Over { x: i32, y: i32 },
+    Drop { paths: Vec<String>, x: i32, y: i32 },
+    /// The drag was cancelled or left the webview.
+    Leave,
+}
+
+impl DragDropEvent {
+    fn from_wry(event: wry::DragDropEvent) -> Option<Self> {
+        let paths = |paths: Vec<std::path::PathBuf>| {
+            paths
+                .into_iter()
+                .map(|p| p.to_string_lossy().into_owned())
+                .collect()
+        };
+        Some(match event {
+            wry::DragDropEvent::Enter { paths: p, position } => DragDropEvent::Enter {
+                paths: paths(p),
+                x: position.0,
+                y: position.1,
+            },
+            wry::DragDropEvent::Over { position } => DragDropEvent::Over {
+                x: position.0,
+                y: position.1,
+            },
+            wry::DragDropEvent::Drop { paths: p, position } => DragDropEvent::Drop {
+                paths: paths(p),
+                x: position.0,
+                y: position.1,
+            },
+            wry::DragDropEvent::Leave => DragDropEvent::Leave,
+            _ => return None,
+        })
+    }
+}
+
+#[uniffi::export(callback_interface)]
+pub trait DragDropHandler: Send + Sync {
+    /// Called on the UI thread for each drag event. Return true to handle the
+    /// drop in the host; false lets the page receive it (e.g. `<input type=file>`).
+    fn handle_drag_drop(&self, event: DragDropEvent) -> bool;
+}
+
+/// Routes file drags over the webview to `handler`.
+#[uniffi::export]
+pub fn set_drag_drop_handler(
+    id: u64,
+    handler: Option<Box<dyn DragDropHandler>>,
+) -> Result<(), WebViewError> {
+    let state = get_state(id)?;
+    state.handlers.drag_drop.set(handler.map(Arc::from))
+}
+
+// ============================================================================
+// Editing
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, uniffi::Enum)]
+pub enum EditingCommand {
+    Cut,
+    Copy,
+    Paste,
+    PasteAsPlainText,
+    SelectAll,
+    Undo,
+    Redo,
+}
+
+impl EditingCommand {
+    /// WebKit editing command name.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn as_webkit_name(self) -> &'static str {
+        match self {
+            EditingCommand::Cut => "Cut",
+            EditingCommand::Copy => "Copy",
+            EditingCommand::Paste => "Paste",
+            EditingCommand::PasteAsPlainText => "PasteAsPlainText",
+            EditingCommand::SelectAll => "SelectAll",
+            EditingCommand::Undo => "Undo",
+            EditingCommand::Redo => "Redo",
+        }
+    }
+}
+
+fn execute_editing_command_inner(id: u64, command: EditingCommand) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] execute_editing_command id={} command={:?}", id, command);
+
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::editing::execute(&webview.webview(), command.as_webkit_name());
+        Ok(())
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = command;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Runs an editing command on the focused frame (Linux only).
+#[uniffi::export]
+pub fn execute_editing_command(id: u64, command: EditingCommand) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || execute_editing_command_inner(id, command));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || execute_editing_command_inner(id, command));
+    result
+}
+
+/// Returns whether `command` currently applies, e.g. to enable menu items
+/// (Linux only). Blocks until the engine answers, so it must not be called
+/// from a host callback.
+#[uniffi::export]
+pub fn can_execute_editing_command(
+    id: u64,
+    command: EditingCommand,
+) -> Result<bool, WebViewError> {
+    wry_log!("[wrywebview] can_execute_editing_command id={} command={:?}", id, command);
+
+    #[cfg(target_os = "linux")]
+    let result = platform::linux::wait_on_gtk_thread("can_execute_editing_command", move |reply| {
+        with_webview(id, |webview| {
+            platform::linux::editing::can_execute(
+                &webview.webview(),
+                command.as_webkit_name(),
+                reply,
+            );
+            Ok(())
+        })
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = command;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Returns the selected text, including selections inside text fields
+/// (Linux only). Blocks like `can_execute_editing_command`.
+#[uniffi::export]
+pub fn get_selected_text(id: u64) -> Result<String, WebViewError> {
+    wry_log!("[wrywebview] get_selected_text id={}", id);
+
+    #[cfg(target_os = "linux")]
+    let result = {
+        let text = evaluate_isolated(id, "get_selected_text", scripts::SELECTED_TEXT.to_string())?;
+        Ok(text.as_str().unwrap_or_default().to_string())
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = Err(WebViewError::UnsupportedPlatform);
+    result
+}
+
+// ============================================================================
+// Keyboard Shortcuts
+// ============================================================================
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct KeyEvent {
+    /// The matched accelerator in GTK syntax, e.g. `<Control>t`.
+    pub accelerator: String,
+    /// GDK key name, e.g. `t` or `F5`.
+    pub key: String,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+#[uniffi::export(callback_interface)]
+pub trait KeyEventHandler: Send + Sync {
+    /// Called on the GTK thread for key presses matching a forwarded accelerator.
+    fn handle_key_event(&self, event: KeyEvent);
+}
+
+/// Sends key presses matching the forwarded accelerators to `handler` (Linux only).
+#[uniffi::export]
+pub fn set_key_event_handler(
+    id: u64,
+    handler: Option<Box<dyn KeyEventHandler>>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = {
+        let state = get_state(id)?;
+        state.handlers.key_event.set(handler.map(Arc::from))
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (id, handler);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+fn set_forwarded_accelerators_inner(
+    id: u64,
+    accelerators: Vec<String>,
+) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] set_forwarded_accelerators id={} accelerators={:?}", id, accelerators);
+
+    #[cfg(target_os = "linux")]
+    let result = {
+        // Validates the id; the accelerators are kept by the key handler.
+        get_state(id)?;
+        platform::linux::keys::set_accelerators(id, &accelerators)
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = accelerators;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Sets the accelerators (GTK syntax, e.g. `<Control>t`, `<Control><Shift>Tab`,
+/// `F5`) intercepted while the webview has focus (Linux only). Matching keys are
+/// sent to the `KeyEventHandler` and withheld from the page; others reach it.
+#[uniffi::export]
+pub fn set_forwarded_accelerators(id: u64, accelerators: Vec<String>) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || set_forwarded_accelerators_inner(id, accelerators));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || set_forwarded_accelerators_inner(id, accelerators));
+    result
+}
+
+// ============================================================================
+// Synthetic Input
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, uniffi::Enum)]
+pub enum MouseEventKind {
+    Move,
+    Press,
+    Release,
+}
+
+#[derive(Debug, Clone, Copy, uniffi::Enum)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Default, uniffi::Record)]
+pub struct InputModifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+fn send_mouse_event_inner(
+    id: u64,
+    kind: MouseEventKind,
+    x: f64,
+    y: f64,
+    button: MouseButton,
+    modifiers: InputModifiers,
+) -> Result<(), WebViewError> {
+    wry_log!(
+        "[wrywebview] send_mouse_event id={} kind={:?} x={} y={} button={:?}",
+        id,
+        kind,
+        x,
+        y,
+        button
+    );
+
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::input::send_mouse(&webview.webview(), kind, x, y, button, modifiers)
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (kind, x, y, button, modifiers);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Delivers a synthesized mouse event at `x`, `y` (logical pixels relative to
+/// the webview) through the native event path (Linux only). `button` is
+/// ignored for `Move`.
+#[uniffi::export]
+pub fn send_mouse_event(
+    id: u64,
+    kind: MouseEventKind,
+    x: f64,
+    y: f64,
+    button: MouseButton,
+    modifiers: InputModifiers,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result =
+        run_on_gtk_thread(move || send_mouse_event_inner(id, kind, x, y, button, modifiers));
+    #[cfg(not(target_os = "linux"))]
+    let result =
+        run_on_main_thread(move || send_mouse_event_inner(id, kind, x, y, button, modifiers));
+    result
+}
+
+fn send_key_event_inner(
+    id: u64,
+    key: String,
+    modifiers: InputModifiers,
+    text: Option<String>,
+) -> Result<(), WebViewError> {
+    wry_log!(
+        "[wrywebview] send_key_event id={} key={} modifiers={:?} text_len={}",
+        id,
+        key,
+        modifiers,
+        text.as_ref().map_or(0, |t| t.len())
+    );
+
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::input::send_key(&webview.webview(), &key, modifiers, text.as_deref())
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (key, modifiers, text);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Presses and releases `key` (a GDK key name such as `Return`, `Tab` or `a`;
+/// empty for none) with `modifiers`, then types each character of `text`
+/// (Linux only). Keys go to the focused element, so focus the webview first.
+#[uniffi::export]
+pub fn send_key_event(
+    id: u64,
+    key: String,
+    modifiers: InputModifiers,
+    text: Option<String>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || send_key_event_inner(id, key, modifiers, text));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || send_key_event_inner(id, key, modifiers, text));
+    result
+}
+
+fn send_scroll_inner(id: u64, dx: f64, dy: f64) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] send_scroll id={} dx={} dy={}", id, dx, dy);
+
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::input::send_scroll(&webview.webview(), dx, dy)
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (dx, dy);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Delivers a smooth scroll at the webview's center; deltas are in wheel
+/// steps, positive scrolling right/down (Linux only).
+#[uniffi::export]
+pub fn send_scroll(id: u64, dx: f64, dy: f64) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || send_scroll_inner(id, dx, dy));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || send_scroll_inner(id, dx, dy));
+    result
+}
+
+// ============================================================================
+// DOM Automation
+// ============================================================================
+
+/// Delay before `wait_for_selector` retries a check that failed, e.g. because
+/// the document was being replaced.
+#[cfg(target_os = "linux")]
+const SELECTOR_RETRY_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// Evaluates `script` in the isolated world and blocks for its result.
+#[cfg(target_os = "linux")]
+fn evaluate_isolated(
+    id: u64,
+    operation: &'static str,
+    script: String,
+) -> Result<serde_json::Value, WebViewError> {
+    let result = platform::linux::wait_on_gtk_thread(operation, move |reply| {
+        with_webview(id, |webview| {
+            platform::linux::isolated_world::evaluate(&webview.webview(), &script, move |result| {
+                let _ = reply.send(result);
+            });
+            Ok(())
+        })
+    })?;
+    result
+        .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()))
+        .map_err(|e| WebViewError::Internal(format!("{} failed: {}", operation, e)))
+}
+
+/// Returns the text content of the first element matching `selector`, or
+/// `None` without a match (Linux only).
+///
+/// The automation helpers run in an isolated script world, out of the page's
+/// reach, and block until the page answers; they must not be called from a
+/// host callback.
+#[uniffi::export]
+pub fn query_selector_text(id: u64, selector: String) -> Result<Option<String>, WebViewError> {
+    wry_log!("[wrywebview] query_selector_text id={} selector={}", id, selector);
+
+    #[cfg(target_os = "linux")]
+    let result = {
+        let text = evaluate_isolated(
+            id,
+            "query_selector_text",
+            scripts::automation::query_text(&selector),
+        )?;
+        Ok(text.as_str().map(str::to_string))
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = selector;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Scrolls the first element matching `selector` into view and clicks its
+/// center with synthesized mouse events (Linux only).
+#[uniffi::export]
+pub fn click_selector(id: u64, selector: String) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] click_selector id={} selector={}", id, selector);
+
+    #[cfg(target_os = "linux")]
+    let result = {
+        let center = evaluate_isolated(
+            id,
+            "click_selector",
+            scripts::automation::element_center(&selector),
+        )?;
+        let (Some(x), Some(y)) = (
+            center.get("x").and_then(serde_json::Value::as_f64),
+            center.get("y").and_then(serde_json::Value::as_f64),
+        ) else {
+            return Err(WebViewError::ElementNotFound(selector));
+        };
+
+        run_on_gtk_thread(move || {
+            with_webview(id, |webview| {
+                let gtk_webview = webview.webview();
+                let modifiers = InputModifiers::default();
+                for kind in [
+                    MouseEventKind::Move,
+                    MouseEventKind::Press,
+                    MouseEventKind::Release,
+                ] {
+                    platform::linux::input::send_mouse(
+                        &gtk_webview,
+                        kind,
+                        x,
+                        y,
+                        MouseButton::Left,
+                        modifiers,
+                    )?;
+                }
+                Ok(())
+            })
+        })
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = selector;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Focuses the first element matching `selector` and sets its value, firing
+/// `input` and `change` events (Linux only).
+#[uniffi::export]
+pub fn fill_selector(id: u64, selector: String, value: String) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] fill_selector id={} selector={}", id, selector);
+
+    #[cfg(target_os = "linux")]
+    let result = {
+        let filled = evaluate_isolated(
+            id,
+            "fill_selector",
+            scripts::automation::fill(&selector, &value),
+        )?;
+        if filled.as_bool() != Some(true) {
+            return Err(WebViewError::ElementNotFound(selector));
+        }
+        Ok(())
+    };
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (selector, value);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Blocks until an element matches `selector`, including across navigations
+/// (Linux only). The page is checked again when a load finishes or when a
+/// DOM observer in the isolated world reports a match.
+#[uniffi::export]
+pub fn wait_for_selector(id: u64, selector: String, timeout_ms: u64) -> Result<(), WebViewError> {
+    wry_log!(
+        "[wrywebview] wait_for_selector id={} selector={} timeout_ms={}",
+        id,
+        selector,
+        timeout_ms
+    );
+
+    #[cfg(target_os = "linux")]
+    {
+        platform::linux::ensure_off_gtk_thread("wait_for_selector")?;
+        let state = get_state(id)?;
+        let deadline = std::time::Instant::now() + std::time::Duration::from_millis(timeout_ms);
+        loop {
+            // Read first, so a change during the check is not missed.
+            let seen = state.page_changes()?;
+            let retry = match evaluate_isolated(
+                id,
+                "wait_for_selector",
+                scripts::automation::watch(&selector),
+            ) {
+                Ok(found) if found.as_bool() == Some(true) => return Ok(()),
+                Ok(_) => None,
+                // Navigations cancel scripts; that counts as "not found yet".
+                Err(e @ (WebViewError::Internal(_) | WebViewError::Timeout(_))) => {
+                    wry_log!("[wrywebview] wait_for_selector check failed: {}", e);
+                    Some(SELECTOR_RETRY_INTERVAL)
+                }
+                Err(e) => return Err(e),
+            };
+
+            let now = std::time::Instant::now();
+            if now >= deadline {
+                return Err(WebViewError::Timeout(format!("selector {}", selector)));
+            }
+            let remaining = deadline - now;
+            state.wait_for_page_change(seen, retry.map_or(remaining, |retry| retry.min(remaining)))?;
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = (selector, timeout_ms);
+        Err(WebViewError::UnsupportedPlatform)
+    }
+}
+
+/// Blocks until the current page load finishes. Returns immediately when
+/// nothing is loading. Must not be called from the UI thread.
+#[uniffi::export]
+pub fn wait_for_load(id: u64, timeout_ms: u64) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] wait_for_load id={} timeout_ms={}", id, timeout_ms);
+
+    #[cfg(target_os = "linux")]
+    platform::linux::ensure_off_gtk_thread("wait_for_load")?;
+    #[cfg(not(target_os = "linux"))]
+    state::ensure_off_owner_thread(id, "wait_for_load")?;
+
+    let state = get_state(id)?;
+    if state.wait_until_loaded(std::time::Duration::from_millis(timeout_ms))? {
+        Ok(())
+    } else {
+        Err(WebViewError::Timeout("page load".to_string()))
+    }
+}
+
+// ============================================================================
+// Find in Page
+// ============================================================================
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct FindOptions {
+    pub case_sensitive: bool,
+    /// Continue from the other end of the page after the last match.
+    pub wrap: bool,
+    /// Search towards the start of the page.
+    pub backwards: bool,
+    /// Only match at word starts (WebKitGTK has no exact whole-word matching).
+    pub whole_word: bool,
+}
+
+/// Highlights every match of `query`, selects the first one and returns the
+/// match count (capped at 1000). Blocks until the engine answers, so it must
+/// not be called from a host callback.
+///
+/// Progress is also reported through `FindTextFound`/`FindTextNotFound` events.
+#[uniffi::export]
+pub fn find_text(id: u64, query: String, options: FindOptions) -> Result<u32, WebViewError> {
+    wry_log!("[wrywebview] find_text id={} query={} options={:?}", id, query, options);
+
+    #[cfg(target_os = "linux")]
+    let result = platform::linux::wait_on_gtk_thread("find_text", move |reply| {
+        with_webview(id, |webview| {
+            platform::linux::find::search(&webview.webview(), id, query, &options, reply);
+            Ok(())
+        })
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = (query, options);
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+fn find_step_inner(id: u64, forward: bool) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] find_step id={} forward={}", id, forward);
+
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::find::step(&webview.webview(), id, forward);
+        Ok(())
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = forward;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Selects the next match of the current search; does nothing without one.
+#[uniffi::export]
+pub fn find_next(id: u64) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || find_step_inner(id, true));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || find_step_inner(id, true));
+    result
+}
+
+/// Selects the previous match of the current search; does nothing without one.
+#[uniffi::export]
+pub fn find_previous(id: u64) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || find_step_inner(id, false));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || find_step_inner(id, false));
+    result
+}
+
+fn clear_find_inner(id: u64) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] clear_find id={}", id);
+
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::find::clear(&webview.webview(), id);
+        Ok(())
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = Err(WebViewError::UnsupportedPlatform);
+    result
+}
+
+/// Ends the current search and removes its highlights.
+#[uniffi::export]
+pub fn clear_find(id: u64) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || clear_find_inner(id));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || clear_find_inner(id));
+    result
+}
+
+// ============================================================================
+// Scrolling
+// ============================================================================
+
+/// Returns the last reported scroll position of the top-level document.
+#[uniffi::export]
+pub fn get_scroll_position(id: u64) -> Result<ScrollPosition, WebViewError> {
+    let state = get_state(id)?;
+    let position = state
+        .scroll_position
+        .lock()
+        .map_err(|_| WebViewError::Internal("scroll position lock poisoned".to_string()))?;
+    Ok(*position)
+}
+
+fn scroll_to_inner(id: u64, x: f64, y: f64, smooth: bool) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] scroll_to id={} x={} y={} smooth={}", id, x, y, smooth);
+    if !(x.is_finite() && y.is_finite()) {
+        return Err(WebViewError::Internal(format!("invalid scroll position: {}, {}", x, y)));
+    }
+    let script = format!(
+        "window.scrollTo({{ left: {}, top: {}, behavior: '{}' }});",
+        x,
+        y,
+        if smooth { "smooth" } else { "instant" }
+    );
+
+    with_webview(id, |webview| {
+        #[cfg(target_os = "linux")]
+        let result = {
+            platform::linux::isolated_world::evaluate(&webview.webview(), &script, |_| {});
+            Ok(())
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = webview.evaluate_script(&script).map_err(WebViewError::from);
+        result
+    })
+}
+
+/// Scrolls the top-level document to `x`, `y` (logical pixels).
+///
+/// On Linux this runs in an isolated world, unaffected by page overrides of `window.scrollTo`.
+#[uniffi::export]
+pub fn scroll_to(id: u64, x: f64, y: f64, smooth: bool) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || scroll_to_inner(id, x, y, smooth));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || scroll_to_inner(id, x, y, smooth));
+    result
+}
+
+// ============================================================================
+// Snapshots
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, uniffi::Enum)]
+pub enum SnapshotRegion {
+    /// The currently visible viewport.
+    Visible,
+    /// The whole document, including parts scrolled out of view.
+    FullDocument,
+}
+
+#[uniffi::export(callback_interface)]
+pub trait SnapshotCallback: Send + Sync {
+    /// Receives the snapshot as PNG bytes.
+    fn on_snapshot(&self, png: Vec<u8>);
+
+    fn on_error(&self, message: String);
+}
+
+fn capture_snapshot_inner(
+    id: u64,
+    region: SnapshotRegion,
+    scale: f64,
+    callback: Box<dyn SnapshotCallback>,
+) -> Result<(), WebViewError> {
+    wry_log!(
+        "[wrywebview] capture_snapshot id={} region={:?} scale={}",
+        id,
+        region,
+        scale
+    );
+    if !(scale.is_finite() && scale > 0.0) {
+        return Err(WebViewError::Internal(format!("invalid snapshot scale: {}", scale)));
+    }
+
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::snapshot::capture(&webview.webview(), region, scale, callback);
+        Ok(())
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = callback;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Renders the page to PNG; `scale` resizes the result (1.0 keeps device pixels).
+/// The callback is invoked asynchronously once rendering completes.
+#[uniffi::export]
+pub fn capture_snapshot(
+    id: u64,
+    region: SnapshotRegion,
+    scale: f64,
+    callback: Box<dyn SnapshotCallback>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || capture_snapshot_inner(id, region, scale, callback));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || capture_snapshot_inner(id, region, scale, callback));
+    result
+}
+
+// ============================================================================
+// Printing
+// ============================================================================
+
+#[derive(Debug, Clone, uniffi::Enum)]
+pub enum PdfPageSize {
+    A3,
+    A4,
+    A5,
+    Letter,
+    Legal,
+    Custom { width_mm: f64, height_mm: f64 },
+}
+
+#[derive(Debug, Clone, Copy, uniffi::Enum)]
+pub enum PdfOrientation {
+    Portrait,
+    Landscape,
+}
+
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct PdfMargins {
+    pub top_mm: f64,
+    pub right_mm: f64,
+    pub bottom_mm: f64,
+    pub left_mm: f64,
+}
+
+/// Layout of a PDF export.
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct PdfPrintOptions {
+    pub page_size: PdfPageSize,
+    pub margins: PdfMargins,
+    pub orientation: PdfOrientation,
+    /// Prints CSS backgrounds (colors and images).
+    pub print_backgrounds: bool,
+    /// Adds the title, URL and page numbers around each page. WebKitGTK
+    /// cannot, so `true` fails with `UnsupportedOption` on Linux.
+    pub header_footer: bool,
+}
+
+fn print_to_pdf_inner(
+    id: u64,
+    path: String,
+    options: PdfPrintOptions,
+    callback: Box<dyn CompletionCallback>,
+) -> Result<(), WebViewError> {
+    wry_log!(
+        "[wrywebview] print_to_pdf id={} path={} options={:?}",
+        id,
+        path,
+        options
+    );
+
+    #[cfg(target_os = "linux")]
+    let result = with_webview(id, |webview| {
+        platform::linux::print::print_to_pdf(&webview.webview(), &path, &options, callback)
+    });
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        let _ = callback;
+        Err(WebViewError::UnsupportedPlatform)
+    };
+    result
+}
+
+/// Writes the page to a PDF file at `path` without showing a dialog.
+/// The callback is invoked asynchronously once the file is written.
+#[uniffi::export]
+pub fn print_to_pdf(
+    id: u64,
+    path: String,
+    options: PdfPrintOptions,
+    callback: Box<dyn CompletionCallback>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || print_to_pdf_inner(id, path, options, callback));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || print_to_pdf_inner(id, path, options, callback));
+    result
+}
+
+fn print_inner(id: u64) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] print id={}", id);
+    with_webview(id, |webview| {
+        #[cfg(target_os = "linux")]
+        let result = {
+            platform::linux::print::print_with_dialog(&webview.webview());
+            Ok(())
+        };
+        #[cfg(not(target_os = "linux"))]
+        let result = webview.print().map_err(WebViewError::from);
+        result
+    })
+}
+
+/// Opens the native print dialog; returns without waiting for it to close.
+#[uniffi::export]
+pub fn print(id: u64) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || print_inner(id));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || print_inner(id));
+    result
+}
+
+// ============================================================================
+// Page Source & Archives
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, uniffi::Enum)]
+pub enum SavePageFormat {
+    /// Single-file web archive with subresources (Linux only).
+    Mhtml,
+    /// The serialized DOM as HTML.
+    HtmlSource,
+}
+
+/// Evaluates the page serialization script and decodes its JSON string result.
+fn with_page_source<F>(webview: &wry::WebView, f: F) -> Result<(), WebViewError>
+where
+    F: Fn(Result<String, String>) + Send + 'static,
+{
+    webview
+        .evaluate_script_with_callback(scripts::PAGE_SOURCE, move |result| {
+            f(serde_json::from_str::<String>(&result)
+                .map_err(|e| format!("unexpected page source result: {}", e)))
+        })
+        .map_err(WebViewError::from)
+}
+
+fn start_page_source(
+    id: u64,
+    reply: std::sync::mpsc::SyncSender<Result<String, String>>,
+) -> Result<(), WebViewError> {
+    with_webview(id, |webview| {
+        with_page_source(webview, move |result| {
+            let _ = reply.send(result);
+        })
+    })
+}
+
+/// Returns the current DOM serialized as HTML, doctype included.
+///
+/// Blocks until the page answers, so it must not be called from the UI thread
+/// or from a host callback.
+#[uniffi::export]
+pub fn get_page_source(id: u64) -> Result<String, WebViewError> {
+    wry_log!("[wrywebview] get_page_source id={}", id);
+
+    #[cfg(target_os = "linux")]
+    let result = platform::linux::wait_on_gtk_thread("get_page_source", move |reply| {
+        start_page_source(id, reply)
+    })?;
+
+    #[cfg(not(target_os = "linux"))]
+    let result = {
+        state::ensure_off_owner_thread(id, "get_page_source")?;
+        let (reply_tx, reply_rx) = std::sync::mpsc::sync_channel(1);
+        run_on_main_thread(move || start_page_source(id, reply_tx))?;
+        reply_rx
+            .recv_timeout(platform::ENGINE_REPLY_TIMEOUT)
+            .map_err(|_| WebViewError::Timeout("get_page_source".to_string()))?
+    };
+
+    result.map_err(|e| WebViewError::Internal(format!("get_page_source failed: {}", e)))
+}
+
+fn save_page_inner(
+    id: u64,
+    format: SavePageFormat,
+    path: String,
+    callback: Box<dyn CompletionCallback>,
+) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] save_page id={} format={:?} path={}", id, format, path);
+    with_webview(id, |webview| match format {
+        SavePageFormat::HtmlSource => {
+            let callback: Arc<dyn CompletionCallback> = Arc::from(callback);
+            with_page_source(webview, move |result| {
+                let source = match result {
+                    Ok(source) => source,
+                    Err(message) => return callback.on_error(message),
+                };
+                let (path, callback) = (path.clone(), Arc::clone(&callback));
+                // Writing a large page must not stall the UI thread, which runs
+                // every WebView.
+                std::thread::spawn(move || {
+                    match std::fs::write(&path, source) {
+                        Ok(()) => callback.on_finished(),
+                        Err(e) => callback.on_error(format!("cannot write {}: {}", path, e)),
+                    }
+                });
+            })
+        }
+        SavePageFormat::Mhtml => {
+            #[cfg(target_os = "linux")]
+            let result = {
+                platform::linux::save::save_mhtml(&webview.webview(), &path, callback);
+                Ok(())
+            };
+            #[cfg(not(target_os = "linux"))]
+            let result = {
+                let _ = callback;
+                Err(WebViewError::UnsupportedPlatform)
+            };
+            result
+        }
+    })
+}
+
+/// Saves the page to `path`; the callback is invoked once the file is written,
+/// possibly from a background thread. The file is written off the UI thread.
+#[uniffi::export]
+pub fn save_page(
+    id: u64,
+    format: SavePageFormat,
+    path: String,
+    callback: Box<dyn CompletionCallback>,
+) -> Result<(), WebViewError> {
+    #[cfg(target_os = "linux")]
+    let result = run_on_gtk_thread(move || save_page_inner(id, format, path, callback));
+    #[cfg(not(target_os = "linux"))]
+    let result = run_on_main_thread(move || save_page_inner(id, format, path, callback));
+    result
+}
+
+// ============================================================================
+// Destruction
+// ============================================================================
+
+fn destroy_webview_inner(id: u64) -> Result<(), WebViewError> {
+    wry_log!("[wrywebview] destroy_webview id={}", id);
+    #[cfg(target_os = "linux")]
+    platform::linux::release_pending_requests(id);
+    unregister(id)?;
+    #[cfg(target_os = "linux")]
+    platform::linux::offscreen::destroy(id);
+    Ok(())
 }
 
 #[uniffi::export]
@@ -1,15 +1,44 @@
 //! Linux-specific GTK thread management.
 
+pub mod backend;
+pub mod context_menu;
+pub mod dialogs;
+pub mod editing;
+pub mod favicon;
+pub mod file_chooser;
+pub mod find;
+pub mod focus;
+pub mod fullscreen;
+pub mod host_window;
+pub mod hover;
+pub mod input;
+pub mod isolated_world;
+pub mod keys;
+pub mod offscreen;
+pub mod permission_policies;
+pub mod permissions;
+pub mod print;
+pub mod process;
+pub mod rendering;
+pub mod save;
+pub mod snapshot;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::mpsc;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
+use std::thread::ThreadId;
 use std::time::Duration;
 
+use super::ENGINE_REPLY_TIMEOUT;
 use crate::error::WebViewError;
+use crate::state::WebViewState;
 
 type GtkTask = Box<dyn FnOnce() + Send + 'static>;
 
 struct GtkRunner {
     sender: mpsc::Sender<GtkTask>,
+    thread_id: ThreadId,
     init_error: Option<String>,
 }
 
@@ -20,7 +49,7 @@ fn gtk_runner() -> Result<&'static GtkRunner, WebViewError> {
         let (task_tx, task_rx) = mpsc::channel::<GtkTask>();
         let (init_tx, init_rx) = mpsc::sync_channel::<Result<(), String>>(1);
 
-        std::thread::spawn(move || {
+        let thread = std::thread::spawn(move || {
             let init_result = gtk::init().map_err(|err| err.to_string());
             let _ = init_tx.send(init_result.clone());
 
@@ -28,15 +57,15 @@ fn gtk_runner() -> Result<&'static GtkRunner, WebViewError> {
                 return;
             }
 
-            loop {
+            // Tasks are drained from a GLib source so they keep running while a
+            // nested main loop (modal dialogs, print dialogs) is active.
+            glib::timeout_add_local(Duration::from_millis(8), move || {
                 while let Ok(task) = task_rx.try_recv() {
                     task();
                 }
-                while gtk::events_pending() {
-                    gtk::main_iteration_do(false);
-                }
-                std::thread::sleep(Duration::from_millis(8));
-            }
+                glib::ControlFlow::Continue
+            });
+            gtk::main();
         });
 
         let init_result = init_rx
@@ -45,6 +74,7 @@ fn gtk_runner() -> Result<&'static GtkRunner, WebViewError> {
 
         GtkRunner {
             sender: task_tx,
+            thread_id: thread.thread().id(),
             init_error: init_result.err(),
         }
     });
@@ -63,6 +93,12 @@ where
     R: Send + 'static,
 {
     let runner = gtk_runner()?;
+
+    // Host callbacks run on the GTK thread and may call back into the library.
+    if runner.thread_id == std::thread::current().id() {
+        return f();
+    }
+
     let (result_tx, result_rx) = mpsc::sync_channel(1);
 
     runner
@@ -78,7 +114,115 @@ where
         .map_err(|_| WebViewError::Internal("gtk runner stopped".to_string()))?
 }
 
+/// Fails if called on the GTK thread, which must not block waiting on itself.
+pub fn ensure_off_gtk_thread(operation: &str) -> Result<(), WebViewError> {
+    let runner = gtk_runner()?;
+    if runner.thread_id == std::thread::current().id() {
+        return Err(WebViewError::Internal(format!(
+            "{} cannot block the GTK thread",
+            operation
+        )));
+    }
+    Ok(())
+}
+
+/// Starts an asynchronous engine operation on the GTK thread and blocks until
+/// it replies through the sender passed to `start`.
+///
+/// Replies are delivered by the GTK main loop, so the GTK thread itself (host
+/// callbacks included) cannot wait on them.
+pub fn wait_on_gtk_thread<F, R>(operation: &str, start: F) -> Result<R, WebViewError>
+where
+    F: FnOnce(mpsc::SyncSender<R>) -> Result<(), WebViewError> + Send + 'static,
+    R: Send + 'static,
+{
+    ensure_off_gtk_thread(operation)?;
+
+    let (reply_tx, reply_rx) = mpsc::sync_channel(1);
+    run_on_gtk_thread(move || start(reply_tx))?;
+
+    reply_rx
+        .recv_timeout(ENGINE_REPLY_TIMEOUT)
+        .map_err(|err| match err {
+            mpsc::RecvTimeoutError::Timeout => WebViewError::Timeout(operation.to_string()),
+            mpsc::RecvTimeoutError::Disconnected => {
+                WebViewError::Internal(format!("{} was cancelled", operation))
+            }
+        })
+}
+
 /// Ensures GTK is initialized on the current thread.
 pub fn ensure_gtk_initialized() -> Result<(), WebViewError> {
     gtk::init().map_err(|err| WebViewError::GtkInit(err.to_string()))
 }
+
+/// Engine requests kept alive on the GTK thread until the host answers them.
+pub struct PendingRequests<T> {
+    entries: RefCell<HashMap<u64, (u64, T)>>,
+}
+
+impl<T> PendingRequests<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: RefCell::new(HashMap::new()),
+        }
+    }
+
+    pub fn insert(&self, request_id: u64, webview_id: u64, request: T) {
+        self.entries
+            .borrow_mut()
+            .insert(request_id, (webview_id, request));
+    }
+
+    /// Removes a request, provided it belongs to `webview_id`.
+    pub fn take(&self, webview_id: u64, request_id: u64) -> Option<T> {
+        let mut entries = self.entries.borrow_mut();
+        match entries.get(&request_id) {
+            Some((owner, _)) if *owner == webview_id => {
+                entries.remove(&request_id).map(|(_, request)| request)
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes every request belonging to `webview_id`.
+    pub fn take_all(&self, webview_id: u64) -> Vec<T> {
+        let mut entries = self.entries.borrow_mut();
+        let ids: Vec<u64> = entries
+            .iter()
+            .filter(|(_, (owner, _))| *owner == webview_id)
+            .map(|(request_id, _)| *request_id)
+            .collect();
+        ids.into_iter()
+            .filter_map(|request_id| entries.remove(&request_id))
+            .map(|(_, request)| request)
+            .collect()
+    }
+}
+
+/// Connects the WebKitGTK signals backing host callbacks and events.
+pub fn connect_webview_signals(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    dialogs::connect(webview, id, state);
+    permissions::connect(webview, id, state);
+    file_chooser::connect(webview, id, state);
+    fullscreen::connect(webview, id, state);
+    find::connect(webview, id, state);
+    favicon::connect(webview, id, state);
+    hover::connect(webview, id, state);
+    context_menu::connect(webview, id, state);
+    keys::connect(webview, id, state);
+    focus::connect(webview, id, state);
+    process::connect(webview, id, state);
+}
+
+/// Releases requests still waiting on the host for a WebView being destroyed.
+pub fn release_pending_requests(id: u64) {
+    dialogs::discard_pending(id);
+    permissions::discard_pending(id);
+    file_chooser::discard_pending(id);
+    find::discard(id);
+    keys::discard(id);
+    focus::discard(id);
+    process::discard(id);
+    rendering::discard(id);
+}
@@ -0,0 +1,44 @@
+//! Runtime detection of the GDK windowing backend.
+
+use gtk::glib::ObjectExt;
+
+use crate::error::WebViewError;
+
+/// The windowing system GDK connected to during `gtk::init`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    X11,
+    Wayland,
+    Other,
+}
+
+/// Returns the backend of the default GDK display. GTK must be initialized.
+pub fn current() -> Backend {
+    let Some(display) = gdk::Display::default() else {
+        return Backend::Other;
+    };
+    if display.is::<gdkx11::X11Display>() {
+        return Backend::X11;
+    }
+    // gdk-wayland has no Rust bindings; the type name is stable across GTK 3.
+    if display.type_().name() == "GdkWaylandDisplay" {
+        return Backend::Wayland;
+    }
+    Backend::Other
+}
+
+/// Whether X11 calls (XSetInputFocus, XQueryTree) may be made on the default display.
+pub fn is_x11() -> bool {
+    current() == Backend::X11
+}
+
+/// Explains why a Wayland `wl_surface` cannot parent a WebView on this backend.
+pub fn wayland_surface_error() -> WebViewError {
+    let reason = match current() {
+        Backend::Wayland => {
+            "WebKitGTK cannot be embedded into a foreign wl_surface; use a GtkContainer from create_gtk_host_window instead"
+        }
+        _ => "a wl_surface handle was given but GDK is not running on Wayland",
+    };
+    WebViewError::InvalidWindowHandle(reason.to_string())
+}
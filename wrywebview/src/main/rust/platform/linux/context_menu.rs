@@ -0,0 +1,196 @@
+//! Context menu filtering and host-defined items.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use gtk::gio::prelude::ActionExt;
+use gtk::gio::SimpleAction;
+use webkit2gtk::{
+    ContextMenu, ContextMenuAction, ContextMenuExt, ContextMenuItem as WebKitContextMenuItem,
+    ContextMenuItemExt, HitTestResultExt, WebViewExt,
+};
+
+use crate::log_enabled;
+use crate::state::WebViewState;
+use crate::{ContextMenuInfo, ContextMenuItem, ContextMenuResponse, WebViewEvent};
+
+/// Source of unique action names for custom items.
+static NEXT_ACTION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Stable names of WebKit's stock actions, as documented on
+/// `ContextMenuItem::Builtin`.
+fn stock_action_name(action: ContextMenuAction) -> Option<&'static str> {
+    Some(match action {
+        ContextMenuAction::NoAction => "NoAction",
+        ContextMenuAction::OpenLink => "OpenLink",
+        ContextMenuAction::OpenLinkInNewWindow => "OpenLinkInNewWindow",
+        ContextMenuAction::DownloadLinkToDisk => "DownloadLinkToDisk",
+        ContextMenuAction::CopyLinkToClipboard => "CopyLinkToClipboard",
+        ContextMenuAction::OpenImageInNewWindow => "OpenImageInNewWindow",
+        ContextMenuAction::DownloadImageToDisk => "DownloadImageToDisk",
+        ContextMenuAction::CopyImageToClipboard => "CopyImageToClipboard",
+        ContextMenuAction::CopyImageUrlToClipboard => "CopyImageUrlToClipboard",
+        ContextMenuAction::OpenFrameInNewWindow => "OpenFrameInNewWindow",
+        ContextMenuAction::GoBack => "GoBack",
+        ContextMenuAction::GoForward => "GoForward",
+        ContextMenuAction::Stop => "Stop",
+        ContextMenuAction::Reload => "Reload",
+        ContextMenuAction::Copy => "Copy",
+        ContextMenuAction::Cut => "Cut",
+        ContextMenuAction::Paste => "Paste",
+        ContextMenuAction::Delete => "Delete",
+        ContextMenuAction::SelectAll => "SelectAll",
+        ContextMenuAction::InputMethods => "InputMethods",
+        ContextMenuAction::Unicode => "Unicode",
+        ContextMenuAction::SpellingGuess => "SpellingGuess",
+        ContextMenuAction::NoGuessesFound => "NoGuessesFound",
+        ContextMenuAction::IgnoreSpelling => "IgnoreSpelling",
+        ContextMenuAction::LearnSpelling => "LearnSpelling",
+        ContextMenuAction::IgnoreGrammar => "IgnoreGrammar",
+        ContextMenuAction::FontMenu => "FontMenu",
+        ContextMenuAction::Bold => "Bold",
+        ContextMenuAction::Italic => "Italic",
+        ContextMenuAction::Underline => "Underline",
+        ContextMenuAction::Outline => "Outline",
+        ContextMenuAction::InspectElement => "InspectElement",
+        ContextMenuAction::OpenVideoInNewWindow => "OpenVideoInNewWindow",
+        ContextMenuAction::OpenAudioInNewWindow => "OpenAudioInNewWindow",
+        ContextMenuAction::CopyVideoLinkToClipboard => "CopyVideoLinkToClipboard",
+        ContextMenuAction::CopyAudioLinkToClipboard => "CopyAudioLinkToClipboard",
+        ContextMenuAction::ToggleMediaControls => "ToggleMediaControls",
+        ContextMenuAction::ToggleMediaLoop => "ToggleMediaLoop",
+        ContextMenuAction::EnterVideoFullscreen => "EnterVideoFullscreen",
+        ContextMenuAction::MediaPlay => "MediaPlay",
+        ContextMenuAction::MediaPause => "MediaPause",
+        ContextMenuAction::MediaMute => "MediaMute",
+        ContextMenuAction::DownloadVideoToDisk => "DownloadVideoToDisk",
+        ContextMenuAction::DownloadAudioToDisk => "DownloadAudioToDisk",
+        ContextMenuAction::InsertEmoji => "InsertEmoji",
+        ContextMenuAction::PasteAsPlainText => "PasteAsPlainText",
+        _ => return None,
+    })
+}
+
+/// Names an item of the menu at `index`. Items without a stock action are
+/// named after their GAction, or their position when they have none.
+fn action_name(item: &WebKitContextMenuItem, index: usize) -> String {
+    if let Some(name) = stock_action_name(item.stock_action()) {
+        return name.to_string();
+    }
+    match item.gaction() {
+        Some(action) => format!("Custom:{}", action.name()),
+        None => format!("Custom:{}", index),
+    }
+}
+
+fn describe(index: usize, item: &WebKitContextMenuItem) -> ContextMenuItem {
+    if item.is_separator() {
+        ContextMenuItem::Separator
+    } else {
+        ContextMenuItem::Builtin {
+            action: action_name(item, index),
+        }
+    }
+}
+
+fn custom_item(
+    id: u64,
+    item_id: String,
+    label: &str,
+    enabled: bool,
+    state: &Arc<WebViewState>,
+) -> WebKitContextMenuItem {
+    let name = format!(
+        "wrywebview-item-{}",
+        NEXT_ACTION_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let action = SimpleAction::new(&name, None);
+    action.set_enabled(enabled);
+    let state = Arc::clone(state);
+    action.connect_activate(move |_, _| {
+        if log_enabled() {
+            eprintln!("[wrywebview] context_menu_item id={} item={}", id, item_id);
+        }
+        let _ = state.push_event(WebViewEvent::ContextMenuItemActivated {
+            item_id: item_id.clone(),
+        });
+    });
+    WebKitContextMenuItem::from_gaction(&action, label, None)
+}
+
+/// Replaces the menu's items; built-in items are reused from the original menu.
+fn rebuild(menu: &ContextMenu, items: Vec<ContextMenuItem>, id: u64, state: &Arc<WebViewState>) {
+    let mut originals: Vec<(String, WebKitContextMenuItem)> = menu
+        .items()
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| (action_name(&item, index), item))
+        .collect();
+    menu.remove_all();
+    for item in items {
+        match item {
+            ContextMenuItem::Builtin { action } => {
+                let Some(index) = originals.iter().position(|(name, _)| *name == action) else {
+                    if log_enabled() {
+                        eprintln!(
+                            "[wrywebview] context_menu id={} unknown item {}",
+                            id, action
+                        );
+                    }
+                    continue;
+                };
+                menu.append(&originals.remove(index).1);
+            }
+            ContextMenuItem::Custom {
+                id: item_id,
+                label,
+                enabled,
+            } => {
+                menu.append(&custom_item(id, item_id, &label, enabled, state));
+            }
+            ContextMenuItem::Separator => menu.append(&WebKitContextMenuItem::new_separator()),
+        }
+    }
+}
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state = Arc::clone(state);
+    webview.connect_context_menu(move |_, menu, event, hit| {
+        let Some(handler) = state.handlers.context_menu.get() else {
+            return false;
+        };
+        let (x, y) = event.coords().unwrap_or_default();
+        let info = ContextMenuInfo {
+            link_url: hit.link_uri().map(|s| s.to_string()),
+            image_url: hit.image_uri().map(|s| s.to_string()),
+            media_url: hit.media_uri().map(|s| s.to_string()),
+            has_selection: hit.context_is_selection(),
+            is_editable: hit.context_is_editable(),
+            x,
+            y,
+            items: menu
+                .items()
+                .iter()
+                .enumerate()
+                .map(|(index, item)| describe(index, item))
+                .collect(),
+        };
+
+        let response = handler.handle_context_menu(info);
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] context_menu id={} response={:?}",
+                id, response
+            );
+        }
+        match response {
+            ContextMenuResponse::ShowDefault => false,
+            ContextMenuResponse::Show { items } => {
+                rebuild(menu, items, id, &state);
+                false
+            }
+            // Returning true stops the emission, so no menu is shown.
+            ContextMenuResponse::Suppress => true,
+        }
+    });
+}
@@ -0,0 +1,94 @@
+//! JavaScript dialog (`alert`/`confirm`/`prompt`/`beforeunload`) forwarding.
+
+use std::sync::Arc;
+
+use webkit2gtk::{ScriptDialog, ScriptDialogType, WebViewExt};
+
+use super::PendingRequests;
+use crate::error::WebViewError;
+use crate::log_enabled;
+use crate::state::{next_request_id, WebViewState};
+use crate::{ScriptDialogKind, ScriptDialogRequest, ScriptDialogResponse};
+
+thread_local! {
+    // Only touched from the GTK thread, where the signals are emitted.
+    static PENDING: PendingRequests<ScriptDialog> = PendingRequests::new();
+}
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state = Arc::clone(state);
+    webview.connect_script_dialog(move |_, dialog| {
+        let Some(handler) = state.handlers.script_dialog.get() else {
+            return false;
+        };
+        let kind = match dialog.dialog_type() {
+            ScriptDialogType::Alert => ScriptDialogKind::Alert,
+            ScriptDialogType::Confirm => ScriptDialogKind::Confirm,
+            ScriptDialogType::Prompt => ScriptDialogKind::Prompt,
+            ScriptDialogType::BeforeUnloadConfirm => ScriptDialogKind::BeforeUnloadConfirm,
+            _ => return false,
+        };
+        let default_text = match kind {
+            ScriptDialogKind::Prompt => dialog.prompt_get_default_text().map(|t| t.to_string()),
+            _ => None,
+        };
+
+        let request_id = next_request_id();
+        let request = ScriptDialogRequest {
+            request_id,
+            kind,
+            message: dialog.message().map(|m| m.to_string()).unwrap_or_default(),
+            default_text,
+        };
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] script_dialog id={} request={} kind={:?}",
+                id, request_id, request.kind
+            );
+        }
+
+        // Registered before the handler runs, so it may respond synchronously.
+        PENDING.with(|pending| pending.insert(request_id, id, dialog.clone()));
+
+        if handler.handle_script_dialog(request) {
+            return true;
+        }
+        PENDING.with(|pending| pending.take(id, request_id));
+        false
+    });
+}
+
+pub fn respond(
+    webview_id: u64,
+    request_id: u64,
+    response: ScriptDialogResponse,
+) -> Result<(), WebViewError> {
+    let Some(dialog) = PENDING.with(|pending| pending.take(webview_id, request_id)) else {
+        return Err(WebViewError::RequestNotFound(request_id));
+    };
+
+    match dialog.dialog_type() {
+        ScriptDialogType::Confirm | ScriptDialogType::BeforeUnloadConfirm => {
+            dialog.confirm_set_confirmed(!matches!(response, ScriptDialogResponse::Cancel));
+        }
+        ScriptDialogType::Prompt => match response {
+            ScriptDialogResponse::Accept => {
+                let text = dialog.prompt_get_default_text().unwrap_or_default();
+                dialog.prompt_set_text(&text);
+            }
+            ScriptDialogResponse::AcceptWithText { text } => dialog.prompt_set_text(&text),
+            // Leaving the text unset makes `prompt()` return null.
+            ScriptDialogResponse::Cancel => {}
+        },
+        _ => {}
+    }
+    dialog.close();
+    Ok(())
+}
+
+/// Closes the dialogs still pending for a WebView, answering them as cancelled.
+pub fn discard_pending(webview_id: u64) {
+    for dialog in PENDING.with(|pending| pending.take_all(webview_id)) {
+        dialog.close();
+    }
+}
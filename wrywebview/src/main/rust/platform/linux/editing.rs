@@ -0,0 +1,47 @@
+//! Editing commands (clipboard, undo/redo, selection).
+
+use std::ptr;
+use std::sync::mpsc::SyncSender;
+
+use gtk::gio;
+use gtk::glib;
+use gtk::glib::translate::ToGlibPtr;
+use webkit2gtk::WebViewExt;
+
+pub fn execute(webview: &webkit2gtk::WebView, command: &str) {
+    webview.execute_editing_command(command);
+}
+
+/// Sends whether `command` can currently be executed.
+///
+/// Calls the C API directly: the binding's callback drops the boolean result.
+pub fn can_execute(webview: &webkit2gtk::WebView, command: &str, reply: SyncSender<bool>) {
+    unsafe extern "C" fn finished(
+        source: *mut glib::gobject_ffi::GObject,
+        result: *mut gio::ffi::GAsyncResult,
+        user_data: glib::ffi::gpointer,
+    ) {
+        let reply = Box::from_raw(user_data as *mut SyncSender<bool>);
+        let mut error = ptr::null_mut();
+        let can_execute = webkit2gtk::ffi::webkit_web_view_can_execute_editing_command_finish(
+            source as *mut webkit2gtk::ffi::WebKitWebView,
+            result,
+            &mut error,
+        );
+        if !error.is_null() {
+            glib::ffi::g_error_free(error);
+        }
+        let _ = reply.send(can_execute != glib::ffi::GFALSE);
+    }
+
+    let user_data = Box::into_raw(Box::new(reply));
+    unsafe {
+        webkit2gtk::ffi::webkit_web_view_can_execute_editing_command(
+            webview.to_glib_none().0,
+            command.to_glib_none().0,
+            ptr::null_mut(),
+            Some(finished),
+            user_data as glib::ffi::gpointer,
+        );
+    }
+}
@@ -0,0 +1,40 @@
+//! Favicon tracking through the WebKitGTK favicon database.
+
+use std::sync::Arc;
+
+use webkit2gtk::{WebContextExt, WebViewExt};
+
+use super::snapshot::encode_png;
+use crate::error::WebViewError;
+use crate::log_enabled;
+use crate::state::WebViewState;
+use crate::WebViewEvent;
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    // Favicons are only loaded once the context has a database; `None` picks
+    // the default location. This runs before the WebView's first load, since
+    // WebKit ignores a database enabled after loading started, and the
+    // directory can only be set once per context.
+    if let Some(context) = webview.context() {
+        if context.favicon_database_directory().is_none() {
+            context.set_favicon_database_directory(None);
+        }
+    }
+
+    let state = Arc::clone(state);
+    webview.connect_favicon_notify(move |_| {
+        if log_enabled() {
+            eprintln!("[wrywebview] favicon_changed id={}", id);
+        }
+        let _ = state.push_event(WebViewEvent::FaviconChanged);
+    });
+}
+
+pub fn favicon_png(webview: &webkit2gtk::WebView) -> Result<Option<Vec<u8>>, WebViewError> {
+    let Some(surface) = webview.favicon() else {
+        return Ok(None);
+    };
+    encode_png(surface, 1.0)
+        .map(Some)
+        .map_err(|e| WebViewError::Internal(format!("favicon encoding failed: {}", e)))
+}
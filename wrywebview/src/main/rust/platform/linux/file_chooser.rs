@@ -0,0 +1,81 @@
+//! `<input type=file>` chooser forwarding.
+
+use std::sync::Arc;
+
+use webkit2gtk::{FileChooserRequest, FileChooserRequestExt, WebViewExt};
+
+use super::PendingRequests;
+use crate::error::WebViewError;
+use crate::log_enabled;
+use crate::state::{next_request_id, WebViewState};
+use crate::FileChooserRequestInfo;
+
+thread_local! {
+    // Only touched from the GTK thread, where the signals are emitted.
+    static PENDING: PendingRequests<FileChooserRequest> = PendingRequests::new();
+}
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state = Arc::clone(state);
+    webview.connect_run_file_chooser(move |_, request| {
+        let Some(handler) = state.handlers.file_chooser.get() else {
+            return false;
+        };
+
+        let request_id = next_request_id();
+        let info = FileChooserRequestInfo {
+            request_id,
+            mime_types: request.mime_types().iter().map(|m| m.to_string()).collect(),
+            select_multiple: request.selects_multiple(),
+            selected_files: request
+                .selected_files()
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+        };
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] run_file_chooser id={} request={} mime_types={:?} multiple={}",
+                id, request_id, info.mime_types, info.select_multiple
+            );
+        }
+
+        // Registered before the handler runs, so it may respond synchronously.
+        PENDING.with(|pending| pending.insert(request_id, id, request.clone()));
+
+        if handler.handle_file_chooser(info) {
+            return true;
+        }
+        PENDING.with(|pending| pending.take(id, request_id));
+        false
+    });
+}
+
+pub fn respond(
+    webview_id: u64,
+    request_id: u64,
+    paths: Option<Vec<String>>,
+) -> Result<(), WebViewError> {
+    let Some(request) = PENDING.with(|pending| pending.take(webview_id, request_id)) else {
+        return Err(WebViewError::RequestNotFound(request_id));
+    };
+
+    match paths {
+        Some(paths) if !paths.is_empty() => {
+            let mut paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+            if !request.selects_multiple() {
+                paths.truncate(1);
+            }
+            request.select_files(&paths);
+        }
+        _ => request.cancel(),
+    }
+    Ok(())
+}
+
+/// Cancels the choosers still pending for a WebView.
+pub fn discard_pending(webview_id: u64) {
+    for request in PENDING.with(|pending| pending.take_all(webview_id)) {
+        request.cancel();
+    }
+}
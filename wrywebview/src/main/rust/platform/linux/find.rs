@@ -0,0 +1,263 @@
+//! Find-in-page through `WebKitFindController`.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::mpsc::SyncSender;
+use std::sync::Arc;
+
+use webkit2gtk::{FindControllerExt, FindOptions as WebKitFindOptions, WebViewExt};
+
+use crate::log_enabled;
+use crate::state::WebViewState;
+use crate::{FindOptions, WebViewEvent};
+
+/// Matches counted and highlighted per search.
+const MAX_MATCH_COUNT: u32 = 1000;
+
+#[derive(Debug, Clone, Copy)]
+enum Step {
+    Search,
+    Next,
+    Previous,
+}
+
+/// The engine reports match counts but not which match is selected, so the
+/// active index is tracked from the steps requested.
+struct FindSession {
+    query: String,
+    wrap: bool,
+    backwards: bool,
+    match_count: u32,
+    active_match: u32,
+    step: Step,
+    reply: Option<SyncSender<u32>>,
+}
+
+impl FindSession {
+    fn advance(&mut self, match_count: u32) {
+        let previous_count = self.match_count;
+        self.match_count = match_count;
+        self.active_match = match self.step {
+            Step::Search if self.backwards => match_count,
+            Step::Search => 1,
+            Step::Next if self.active_match < previous_count => self.active_match + 1,
+            Step::Next => 1,
+            Step::Previous if self.active_match > 1 => self.active_match - 1,
+            Step::Previous => match_count,
+        }
+        .min(match_count);
+    }
+}
+
+thread_local! {
+    // Only touched from the GTK thread, where the signals are emitted.
+    static SESSIONS: RefCell<HashMap<u64, FindSession>> = RefCell::new(HashMap::new());
+}
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let Some(controller) = webview.find_controller() else {
+        return;
+    };
+
+    let state_for_found = Arc::clone(state);
+    controller.connect_found_text(move |_, match_count| {
+        // Counts past the maximum are reported as G_MAXUINT.
+        let match_count = match_count.min(MAX_MATCH_COUNT);
+        let event = SESSIONS.with(|sessions| {
+            let mut sessions = sessions.borrow_mut();
+            let session = sessions.get_mut(&id)?;
+            session.advance(match_count);
+            if let Some(reply) = session.reply.take() {
+                let _ = reply.send(match_count);
+            }
+            Some(WebViewEvent::FindTextFound {
+                query: session.query.clone(),
+                match_count,
+                active_match: session.active_match,
+            })
+        });
+        if let Some(event) = event {
+            if log_enabled() {
+                eprintln!("[wrywebview] find found id={} event={:?}", id, event);
+            }
+            let _ = state_for_found.push_event(event);
+        }
+    });
+
+    let state_for_failed = Arc::clone(state);
+    controller.connect_failed_to_find_text(move |_| {
+        let event = SESSIONS.with(|sessions| {
+            let mut sessions = sessions.borrow_mut();
+            let session = sessions.get_mut(&id)?;
+            if let Some(reply) = session.reply.take() {
+                session.match_count = 0;
+                session.active_match = 0;
+                let _ = reply.send(0);
+            }
+            Some(WebViewEvent::FindTextNotFound {
+                query: session.query.clone(),
+            })
+        });
+        if let Some(event) = event {
+            if log_enabled() {
+                eprintln!("[wrywebview] find failed id={} event={:?}", id, event);
+            }
+            let _ = state_for_failed.push_event(event);
+        }
+    });
+}
+
+/// Starts a new search; `reply` receives the match count.
+pub fn search(
+    webview: &webkit2gtk::WebView,
+    id: u64,
+    query: String,
+    options: &FindOptions,
+    reply: SyncSender<u32>,
+) {
+    let Some(controller) = webview.find_controller() else {
+        let _ = reply.send(0);
+        return;
+    };
+    if query.is_empty() {
+        clear(webview, id);
+        let _ = reply.send(0);
+        return;
+    }
+
+    let mut flags = WebKitFindOptions::NONE;
+    if !options.case_sensitive {
+        flags |= WebKitFindOptions::CASE_INSENSITIVE;
+    }
+    if options.wrap {
+        flags |= WebKitFindOptions::WRAP_AROUND;
+    }
+    if options.backwards {
+        flags |= WebKitFindOptions::BACKWARDS;
+    }
+    if options.whole_word {
+        flags |= WebKitFindOptions::AT_WORD_STARTS;
+    }
+
+    // Replacing a session drops its reply sender, cancelling any waiter.
+    SESSIONS.with(|sessions| {
+        sessions.borrow_mut().insert(
+            id,
+            FindSession {
+                query: query.clone(),
+                wrap: options.wrap,
+                backwards: options.backwards,
+                match_count: 0,
+                active_match: 0,
+                step: Step::Search,
+                reply: Some(reply),
+            },
+        )
+    });
+    controller.search(&query, flags.bits(), MAX_MATCH_COUNT);
+}
+
+/// Moves to the next or previous match of the current search.
+pub fn step(webview: &webkit2gtk::WebView, id: u64, forward: bool) {
+    let Some(controller) = webview.find_controller() else {
+        return;
+    };
+    let active = SESSIONS.with(|sessions| {
+        let mut sessions = sessions.borrow_mut();
+        let Some(session) = sessions.get_mut(&id) else {
+            return false;
+        };
+        // Without wrapping there is nothing past the first or last match.
+        let at_end = if forward {
+            session.active_match >= session.match_count
+        } else {
+            session.active_match <= 1
+        };
+        if at_end && !session.wrap {
+            return false;
+        }
+        session.step = if forward { Step::Next } else { Step::Previous };
+        true
+    });
+    if !active {
+        return;
+    }
+    if forward {
+        controller.search_next();
+    } else {
+        controller.search_previous();
+    }
+}
+
+/// Ends the current search and removes its highlights.
+pub fn clear(webview: &webkit2gtk::WebView, id: u64) {
+    discard(id);
+    if let Some(controller) = webview.find_controller() {
+        controller.search_finish();
+    }
+}
+
+/// Forgets the search state of a WebView.
+pub fn discard(id: u64) {
+    SESSIONS.with(|sessions| sessions.borrow_mut().remove(&id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(backwards: bool) -> FindSession {
+        FindSession {
+            query: "needle".to_string(),
+            wrap: true,
+            backwards,
+            match_count: 0,
+            active_match: 0,
+            step: Step::Search,
+            reply: None,
+        }
+    }
+
+    #[test]
+    fn search_selects_first_match_or_last_when_backwards() {
+        let mut forward = session(false);
+        forward.advance(3);
+        assert_eq!((forward.match_count, forward.active_match), (3, 1));
+
+        let mut backward = session(true);
+        backward.advance(3);
+        assert_eq!((backward.match_count, backward.active_match), (3, 3));
+    }
+
+    #[test]
+    fn next_and_previous_wrap_around() {
+        let mut session = session(false);
+        session.advance(3);
+        session.step = Step::Next;
+        session.advance(3);
+        session.advance(3);
+        assert_eq!(session.active_match, 3);
+        session.advance(3);
+        assert_eq!(session.active_match, 1);
+
+        session.step = Step::Previous;
+        session.advance(3);
+        assert_eq!(session.active_match, 3);
+        session.advance(3);
+        assert_eq!(session.active_match, 2);
+    }
+
+    #[test]
+    fn active_match_follows_a_shrinking_count() {
+        let mut session = session(false);
+        session.advance(5);
+        session.step = Step::Next;
+        for _ in 0..3 {
+            session.advance(5);
+        }
+        assert_eq!(session.active_match, 4);
+        // The page changed and now has fewer matches.
+        session.advance(2);
+        assert_eq!((session.match_count, session.active_match), (2, 2));
+    }
+}
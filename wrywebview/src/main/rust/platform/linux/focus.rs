@@ -0,0 +1,208 @@
+//! Focus change reporting and handing focus back to the embedding window.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use gdkx11::glib::translate::ToGlibPtr;
+use gtk::glib::{Cast, ObjectExt, Propagation};
+use gtk::prelude::{GtkWindowExt, WidgetExt};
+use gtk::DirectionType;
+
+use super::backend;
+use crate::log_enabled;
+use crate::state::WebViewState;
+use crate::{FocusDirection, WebViewEvent};
+
+thread_local! {
+    // WebViews embedded in a foreign X11 window, whose toplevel is wry's
+    // container. Only touched from the GTK thread.
+    static CHILD_WINDOWS: RefCell<HashSet<u64>> = RefCell::new(HashSet::new());
+}
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state_for_in = Arc::clone(state);
+    webview.connect_focus_in_event(move |_, _| {
+        if log_enabled() {
+            eprintln!("[wrywebview] focus_in id={}", id);
+        }
+        let _ = state_for_in.push_event(WebViewEvent::FocusChanged { focused: true });
+        Propagation::Proceed
+    });
+
+    let state_for_out = Arc::clone(state);
+    webview.connect_focus_out_event(move |_, _| {
+        if log_enabled() {
+            eprintln!("[wrywebview] focus_out id={}", id);
+        }
+        let _ = state_for_out.push_event(WebViewEvent::FocusChanged { focused: false });
+        Propagation::Proceed
+    });
+}
+
+/// Sets up focus handling for a WebView embedded in a foreign X11 window.
+///
+/// Tabbing past the page's last (or first) focusable element makes WebKit move
+/// focus on from the toplevel. Here the toplevel is wry's container and the
+/// webview is its only child, so GTK would wrap around into the page again;
+/// focus is handed to the host instead. GTK parents handle tabbing themselves.
+pub fn connect_child_window(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    if !backend::is_x11() {
+        return;
+    }
+    let Some(window) = webview
+        .toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok())
+    else {
+        return;
+    };
+    CHILD_WINDOWS.with(|windows| windows.borrow_mut().insert(id));
+    let state = Arc::clone(state);
+    window.connect_focus(move |window, direction| {
+        let direction = match direction {
+            DirectionType::TabForward => FocusDirection::Forward,
+            DirectionType::TabBackward => FocusDirection::Backward,
+            _ => return Propagation::Proceed,
+        };
+        let leaving_page = window
+            .focused_widget()
+            .is_some_and(|widget| widget.is::<webkit2gtk::WebView>());
+        if !leaving_page {
+            return Propagation::Proceed;
+        }
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] focus_leave id={} direction={:?}",
+                id, direction
+            );
+        }
+        release_to_x11_parent(window);
+        let _ = state.push_event(WebViewEvent::FocusReleased { direction });
+        Propagation::Stop
+    });
+}
+
+/// Gives keyboard focus to the webview. When embedded in a foreign X11 window,
+/// GTK alone cannot take the input focus, so it is set on the X11 window too.
+pub fn grab(webview: &webkit2gtk::WebView, id: u64) {
+    webview.set_can_focus(true);
+    if !webview.is_realized() {
+        webview.realize();
+    }
+    if is_child_window(id) {
+        // Offscreen windows have no X11 counterpart.
+        if let Some(gdk_window) = webview
+            .window()
+            .filter(|w| w.toplevel().window_type() != gdk::WindowType::Offscreen)
+        {
+            set_x11_input_focus(&gdk_window);
+        }
+    }
+    webview.grab_focus();
+}
+
+/// Moves the input focus from the webview to the embedding window.
+pub fn release(webview: &webkit2gtk::WebView, id: u64) {
+    let Some(window) = webview
+        .toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok())
+    else {
+        return;
+    };
+    if is_child_window(id) {
+        release_to_x11_parent(&window);
+    } else {
+        window.set_focus(None::<&gtk::Widget>);
+    }
+}
+
+pub fn discard(id: u64) {
+    CHILD_WINDOWS.with(|windows| windows.borrow_mut().remove(&id));
+}
+
+fn is_child_window(id: u64) -> bool {
+    CHILD_WINDOWS.with(|windows| windows.borrow().contains(&id))
+}
+
+fn release_to_x11_parent(window: &gtk::Window) {
+    // Offscreen windows have no X11 counterpart.
+    let Some(gdk_window) = window
+        .window()
+        .filter(|w| w.window_type() != gdk::WindowType::Offscreen)
+    else {
+        return;
+    };
+    let Ok(x11_display) = gdk_window.display().downcast::<gdkx11::X11Display>() else {
+        return;
+    };
+
+    unsafe {
+        let gdk_window_ptr: *mut gdk::ffi::GdkWindow = gdk_window.to_glib_none().0;
+        let xid =
+            gdkx11::ffi::gdk_x11_window_get_xid(gdk_window_ptr as *mut gdkx11::ffi::GdkX11Window);
+        let x11_display_ptr: *mut gdkx11::ffi::GdkX11Display = x11_display.to_glib_none().0;
+        let x_display =
+            gdkx11::ffi::gdk_x11_display_get_xdisplay(x11_display_ptr) as *mut x11::xlib::Display;
+        if xid == 0 || x_display.is_null() {
+            return;
+        }
+
+        // The toplevel is wry's container window; its X11 parent is the host's.
+        let mut root = 0;
+        let mut parent = 0;
+        let mut children = std::ptr::null_mut();
+        let mut child_count = 0;
+        let queried = x11::xlib::XQueryTree(
+            x_display,
+            xid,
+            &mut root,
+            &mut parent,
+            &mut children,
+            &mut child_count,
+        );
+        if !children.is_null() {
+            x11::xlib::XFree(children as *mut _);
+        }
+        if queried == 0 || parent == 0 || parent == root {
+            return;
+        }
+
+        x11::xlib::XSetInputFocus(
+            x_display,
+            parent,
+            x11::xlib::RevertToParent,
+            x11::xlib::CurrentTime,
+        );
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] released X11 focus to parent xid=0x{:x}",
+                parent
+            );
+        }
+    }
+}
+
+fn set_x11_input_focus(gdk_window: &gdk::Window) {
+    let Ok(x11_display) = gdk_window.display().downcast::<gdkx11::X11Display>() else {
+        return;
+    };
+    unsafe {
+        let gdk_window_ptr: *mut gdk::ffi::GdkWindow = gdk_window.to_glib_none().0;
+        let xid =
+            gdkx11::ffi::gdk_x11_window_get_xid(gdk_window_ptr as *mut gdkx11::ffi::GdkX11Window);
+        let x11_display_ptr: *mut gdkx11::ffi::GdkX11Display = x11_display.to_glib_none().0;
+        let x_display = gdkx11::ffi::gdk_x11_display_get_xdisplay(x11_display_ptr);
+        if xid == 0 || x_display.is_null() {
+            return;
+        }
+        x11::xlib::XSetInputFocus(
+            x_display as *mut x11::xlib::Display,
+            xid,
+            x11::xlib::RevertToParent,
+            x11::xlib::CurrentTime,
+        );
+        if log_enabled() {
+            eprintln!("[wrywebview] XSetInputFocus xid=0x{:x}", xid);
+        }
+    }
+}
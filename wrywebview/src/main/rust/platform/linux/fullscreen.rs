@@ -0,0 +1,37 @@
+//! HTML5 fullscreen (`element.requestFullscreen()`) forwarding.
+
+use std::sync::Arc;
+
+use webkit2gtk::WebViewExt;
+
+use crate::log_enabled;
+use crate::state::WebViewState;
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state_for_enter = Arc::clone(state);
+    webview.connect_enter_fullscreen(move |_| {
+        let Some(handler) = state_for_enter.handlers.fullscreen.get() else {
+            return false;
+        };
+        let accepted = handler.handle_enter_fullscreen();
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] enter_fullscreen id={} accepted={}",
+                id, accepted
+            );
+        }
+        // Returning true stops the emission, which makes the engine refuse the request.
+        !accepted
+    });
+
+    let state_for_leave = Arc::clone(state);
+    webview.connect_leave_fullscreen(move |_| {
+        if log_enabled() {
+            eprintln!("[wrywebview] leave_fullscreen id={}", id);
+        }
+        if let Some(handler) = state_for_leave.handlers.fullscreen.get() {
+            handler.handle_leave_fullscreen();
+        }
+        false
+    });
+}
@@ -0,0 +1,98 @@
+//! GTK toplevel windows created for the host to parent WebViews in, where
+//! foreign windows cannot host them (Wayland).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use gtk::glib::translate::ToGlibPtr;
+use gtk::prelude::{BinExt, Cast, GtkWindowExt, WidgetExt, WidgetExtManual};
+
+use crate::error::WebViewError;
+
+struct HostWindow {
+    window: gtk::Window,
+    /// The WebView shown in the window, if any.
+    webview: Option<u64>,
+}
+
+thread_local! {
+    // Only touched from the GTK thread, which owns the windows.
+    static WINDOWS: RefCell<HashMap<u64, HostWindow>> = RefCell::new(HashMap::new());
+}
+
+fn unknown_handle(handle: u64) -> WebViewError {
+    WebViewError::InvalidWindowHandle(format!(
+        "0x{:x} is not a window from create_gtk_host_window",
+        handle
+    ))
+}
+
+/// Shows a new toplevel window and returns its `GtkWindow*`.
+pub fn create(width: i32, height: i32, title: &str) -> u64 {
+    let window = gtk::Window::new(gtk::WindowType::Toplevel);
+    window.set_title(title);
+    window.set_default_size(width.max(1), height.max(1));
+    window.show_all();
+
+    let ptr: *mut gtk::ffi::GtkWindow = window.to_glib_none().0;
+    let handle = ptr as u64;
+    // Runs for both the window manager closing the window and `destroy`,
+    // while the WebView is still inside it.
+    window.connect_destroy(move |_| {
+        let removed = WINDOWS.with(|windows| windows.borrow_mut().remove(&handle));
+        if let Some(id) = removed.and_then(|host| host.webview) {
+            let _ = crate::destroy_webview_inner(id);
+        }
+    });
+    WINDOWS.with(|windows| {
+        windows.borrow_mut().insert(
+            handle,
+            HostWindow {
+                window,
+                webview: None,
+            },
+        )
+    });
+    handle
+}
+
+/// Resolves a handle from `create` into the window to build a WebView in.
+/// Only registered, still empty windows are accepted, so a stale or foreign
+/// pointer is never dereferenced.
+pub fn container(handle: u64) -> Result<gtk::Container, WebViewError> {
+    WINDOWS.with(|windows| {
+        let windows = windows.borrow();
+        let host = windows.get(&handle).ok_or_else(|| unknown_handle(handle))?;
+        if host.window.child().is_some() {
+            return Err(WebViewError::InvalidWindowHandle(format!(
+                "0x{:x} already hosts a WebView",
+                handle
+            )));
+        }
+        Ok(host.window.clone().upcast())
+    })
+}
+
+/// Records that WebView `id` lives in the window, so closing the window
+/// destroys it.
+pub fn adopt(handle: u64, id: u64) {
+    WINDOWS.with(|windows| {
+        if let Some(host) = windows.borrow_mut().get_mut(&handle) {
+            host.webview = Some(id);
+        }
+    });
+}
+
+/// Destroys the window together with the WebView inside it.
+pub fn destroy(handle: u64) -> Result<(), WebViewError> {
+    let window = WINDOWS
+        .with(|windows| {
+            windows
+                .borrow()
+                .get(&handle)
+                .map(|host| host.window.clone())
+        })
+        .ok_or_else(|| unknown_handle(handle))?;
+    unsafe { window.destroy() };
+    Ok(())
+}
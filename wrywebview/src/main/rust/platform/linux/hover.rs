@@ -0,0 +1,42 @@
+//! Hovered link/image reporting (status bar) from `mouse-target-changed`.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use webkit2gtk::{HitTestResultExt, WebViewExt};
+
+use crate::log_enabled;
+use crate::state::WebViewState;
+use crate::WebViewEvent;
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state = Arc::clone(state);
+    // The signal also fires when moving within the same target; report changes only.
+    let last = RefCell::new(None);
+    webview.connect_mouse_target_changed(move |_, hit, _modifiers| {
+        let target = (
+            hit.link_uri().map(|s| s.to_string()),
+            hit.link_title().map(|s| s.to_string()),
+            hit.image_uri().map(|s| s.to_string()),
+            hit.context_is_editable(),
+        );
+        if last.borrow().as_ref() == Some(&target) {
+            return;
+        }
+        last.replace(Some(target.clone()));
+
+        let (link_url, link_title, image_url, is_editable) = target;
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] hovered_target id={} link={:?} image={:?} editable={}",
+                id, link_url, image_url, is_editable
+            );
+        }
+        let _ = state.push_event(WebViewEvent::HoveredTargetChanged {
+            link_url,
+            link_title,
+            image_url,
+            is_editable,
+        });
+    });
+}
@@ -0,0 +1,184 @@
+//! Synthesized GDK input events, delivered through the widget's native event path.
+
+use gtk::gdk::keys::Key;
+use gtk::gdk::{EventType, ModifierType, ScrollDirection};
+use gtk::glib::translate::{IntoGlib, ToGlibPtr, ToGlibPtrMut};
+use gtk::prelude::{SeatExt, WidgetExt};
+
+use crate::error::WebViewError;
+use crate::{InputModifiers, MouseButton, MouseEventKind};
+
+/// The webview's GDK window, its input seat and its root origin.
+struct Target {
+    window: gdk::Window,
+    seat: gdk::Seat,
+    origin: (f64, f64),
+}
+
+fn target(webview: &webkit2gtk::WebView) -> Result<Target, WebViewError> {
+    let window = webview
+        .window()
+        .ok_or_else(|| WebViewError::Internal("webview is not realized".to_string()))?;
+    let seat = window
+        .display()
+        .default_seat()
+        .ok_or_else(|| WebViewError::Internal("no input seat".to_string()))?;
+    let (_, x, y) = window.origin();
+    Ok(Target {
+        window,
+        seat,
+        origin: (x as f64, y as f64),
+    })
+}
+
+fn modifier_state(modifiers: InputModifiers) -> ModifierType {
+    let mut state = ModifierType::empty();
+    if modifiers.ctrl {
+        state |= ModifierType::CONTROL_MASK;
+    }
+    if modifiers.shift {
+        state |= ModifierType::SHIFT_MASK;
+    }
+    if modifiers.alt {
+        state |= ModifierType::MOD1_MASK;
+    }
+    if modifiers.meta {
+        state |= ModifierType::SUPER_MASK;
+    }
+    state
+}
+
+fn button_number(button: MouseButton) -> (u32, ModifierType) {
+    match button {
+        MouseButton::Left => (1, ModifierType::BUTTON1_MASK),
+        MouseButton::Middle => (2, ModifierType::BUTTON2_MASK),
+        MouseButton::Right => (3, ModifierType::BUTTON3_MASK),
+    }
+}
+
+/// Creates an event for `target`; the event holds its own window reference.
+fn new_event(event_type: EventType, target: &Target) -> gdk::Event {
+    let mut event = gdk::Event::new(event_type);
+    unsafe {
+        let raw: *mut gdk::ffi::GdkEvent = event.to_glib_none_mut().0;
+        (*raw).any.window = target.window.to_glib_full();
+        (*raw).any.send_event = 1;
+    }
+    event
+}
+
+pub fn send_mouse(
+    webview: &webkit2gtk::WebView,
+    kind: MouseEventKind,
+    x: f64,
+    y: f64,
+    button: MouseButton,
+    modifiers: InputModifiers,
+) -> Result<(), WebViewError> {
+    let target = target(webview)?;
+    let mut state = modifier_state(modifiers);
+    let (button, button_mask) = button_number(button);
+    let event_type = match kind {
+        MouseEventKind::Move => EventType::MotionNotify,
+        MouseEventKind::Press => EventType::ButtonPress,
+        MouseEventKind::Release => {
+            // GDK reports the released button as still held in release events.
+            state |= button_mask;
+            EventType::ButtonRelease
+        }
+    };
+
+    let mut event = new_event(event_type, &target);
+    unsafe {
+        let raw: *mut gdk::ffi::GdkEvent = event.to_glib_none_mut().0;
+        if let MouseEventKind::Move = kind {
+            let motion = &mut (*raw).motion;
+            motion.time = gtk::current_event_time();
+            motion.x = x;
+            motion.y = y;
+            motion.x_root = target.origin.0 + x;
+            motion.y_root = target.origin.1 + y;
+            motion.state = state.bits();
+        } else {
+            let press = &mut (*raw).button;
+            press.time = gtk::current_event_time();
+            press.x = x;
+            press.y = y;
+            press.x_root = target.origin.0 + x;
+            press.y_root = target.origin.1 + y;
+            press.state = state.bits();
+            press.button = button;
+        }
+    }
+    event.set_device(target.seat.pointer().as_ref());
+    webview.event(&event);
+    Ok(())
+}
+
+fn key_stroke(webview: &webkit2gtk::WebView, target: &Target, key: Key, state: ModifierType) {
+    let keycode = gdk::Keymap::for_display(&target.window.display())
+        .and_then(|keymap| keymap.entries_for_keyval(*key).first().map(|k| k.keycode()))
+        .unwrap_or_default();
+
+    for event_type in [EventType::KeyPress, EventType::KeyRelease] {
+        let mut event = new_event(event_type, target);
+        unsafe {
+            let raw: *mut gdk::ffi::GdkEvent = event.to_glib_none_mut().0;
+            let key_event = &mut (*raw).key;
+            key_event.time = gtk::current_event_time();
+            key_event.state = state.bits();
+            key_event.keyval = *key;
+            key_event.hardware_keycode = keycode as u16;
+        }
+        event.set_device(target.seat.keyboard().as_ref());
+        webview.event(&event);
+    }
+}
+
+pub fn send_key(
+    webview: &webkit2gtk::WebView,
+    key: &str,
+    modifiers: InputModifiers,
+    text: Option<&str>,
+) -> Result<(), WebViewError> {
+    let target = target(webview)?;
+    if !key.is_empty() {
+        let keyval = Key::from_name(key);
+        if *keyval == 0 || *keyval == gdk::ffi::GDK_KEY_VoidSymbol as u32 {
+            return Err(WebViewError::Internal(format!("unknown key name: {}", key)));
+        }
+        key_stroke(webview, &target, keyval, modifier_state(modifiers));
+    }
+    for c in text.unwrap_or_default().chars() {
+        key_stroke(
+            webview,
+            &target,
+            Key::from_unicode(c),
+            ModifierType::empty(),
+        );
+    }
+    Ok(())
+}
+
+pub fn send_scroll(webview: &webkit2gtk::WebView, dx: f64, dy: f64) -> Result<(), WebViewError> {
+    let target = target(webview)?;
+    let x = webview.allocated_width() as f64 / 2.0;
+    let y = webview.allocated_height() as f64 / 2.0;
+
+    let mut event = new_event(EventType::Scroll, &target);
+    unsafe {
+        let raw: *mut gdk::ffi::GdkEvent = event.to_glib_none_mut().0;
+        let scroll = &mut (*raw).scroll;
+        scroll.time = gtk::current_event_time();
+        scroll.x = x;
+        scroll.y = y;
+        scroll.x_root = target.origin.0 + x;
+        scroll.y_root = target.origin.1 + y;
+        scroll.direction = ScrollDirection::Smooth.into_glib();
+        scroll.delta_x = dx;
+        scroll.delta_y = dy;
+    }
+    event.set_device(target.seat.pointer().as_ref());
+    webview.event(&event);
+    Ok(())
+}
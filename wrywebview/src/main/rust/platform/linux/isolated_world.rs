@@ -0,0 +1,56 @@
+//! Library scripts running in an isolated script world.
+//!
+//! The world shares the DOM with the page but not its JavaScript globals, so
+//! pages cannot override or observe what runs there. Its `ipc` message handler
+//! is delivered to wry's IPC handler like the main world's.
+
+use gtk::gio::Cancellable;
+use javascriptcore::ValueExt;
+use webkit2gtk::{
+    UserContentInjectedFrames, UserContentManagerExt, UserScript, UserScriptInjectionTime,
+    WebViewExt,
+};
+
+use crate::log_enabled;
+
+/// Name of the script world used by the library.
+pub const WORLD_NAME: &str = "wrywebview";
+
+/// Registers the world's message handler and adds `scripts` to every top-level document.
+pub fn install(webview: &webkit2gtk::WebView, scripts: &[&str]) {
+    let Some(manager) = webview.user_content_manager() else {
+        return;
+    };
+    manager.register_script_message_handler_in_world("ipc", WORLD_NAME);
+    for source in scripts {
+        manager.add_script(&UserScript::for_world(
+            source,
+            UserContentInjectedFrames::TopFrame,
+            UserScriptInjectionTime::Start,
+            WORLD_NAME,
+            &[],
+            &[],
+        ));
+    }
+}
+
+/// Evaluates `script` in the world; `callback` receives the JSON-encoded result.
+pub fn evaluate<F>(webview: &webkit2gtk::WebView, script: &str, callback: F)
+where
+    F: FnOnce(Result<String, String>) + 'static,
+{
+    webview.run_javascript_in_world(script, WORLD_NAME, None::<&Cancellable>, move |result| {
+        let result = result.map_err(|e| e.to_string()).map(|r| {
+            r.js_value()
+                .and_then(|value| value.to_json(0))
+                .map(|json| json.to_string())
+                .unwrap_or_else(|| "null".to_string())
+        });
+        if let Err(e) = &result {
+            if log_enabled() {
+                eprintln!("[wrywebview] isolated world script failed: {}", e);
+            }
+        }
+        callback(result);
+    });
+}
@@ -0,0 +1,171 @@
+//! Forwarding of host keyboard shortcuts pressed while the webview has focus.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use gtk::gdk;
+use gtk::gdk::keys::Key;
+use gtk::gdk::{EventKey, Keymap, ModifierType};
+use gtk::glib::Propagation;
+use gtk::prelude::WidgetExt;
+
+use crate::error::WebViewError;
+use crate::log_enabled;
+use crate::state::WebViewState;
+use crate::KeyEvent;
+
+/// A parsed accelerator: lowercase key value and modifiers.
+type Accelerator = (u32, ModifierType);
+
+thread_local! {
+    // Only touched from the GTK thread, where key events are delivered.
+    static ACCELERATORS: RefCell<HashMap<u64, Vec<Accelerator>>> = RefCell::new(HashMap::new());
+}
+
+pub fn set_accelerators(id: u64, accelerators: &[String]) -> Result<(), WebViewError> {
+    let parsed = accelerators
+        .iter()
+        .map(|accelerator| {
+            let (key, mods) = gtk::accelerator_parse(accelerator);
+            if key == 0 {
+                return Err(WebViewError::Internal(format!(
+                    "invalid accelerator: {}",
+                    accelerator
+                )));
+            }
+            Ok((*Key::from(key).to_lower(), mods))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    ACCELERATORS.with(|map| map.borrow_mut().insert(id, parsed));
+    Ok(())
+}
+
+pub fn discard(id: u64) {
+    ACCELERATORS.with(|map| map.borrow_mut().remove(&id));
+}
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state = Arc::clone(state);
+    webview.connect_key_press_event(move |_, event| {
+        let matched = ACCELERATORS.with(|map| {
+            map.borrow()
+                .get(&id)
+                .and_then(|accelerators| find_match(event, accelerators))
+        });
+        let Some((key, mods)) = matched else {
+            return Propagation::Proceed;
+        };
+        let Some(handler) = state.handlers.key_event.get() else {
+            return Propagation::Proceed;
+        };
+
+        let key_event = KeyEvent {
+            accelerator: gtk::accelerator_name(key, mods)
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+            key: Key::from(key)
+                .name()
+                .map(|name| name.to_string())
+                .unwrap_or_default(),
+            ctrl: mods.contains(ModifierType::CONTROL_MASK),
+            shift: mods.contains(ModifierType::SHIFT_MASK),
+            alt: mods.contains(ModifierType::MOD1_MASK),
+            meta: mods.intersects(ModifierType::META_MASK | ModifierType::SUPER_MASK),
+        };
+        if log_enabled() {
+            eprintln!("[wrywebview] key_forward id={} event={:?}", id, key_event);
+        }
+        handler.handle_key_event(key_event);
+        Propagation::Stop
+    });
+}
+
+/// Matches a key press the way GTK matches its own accelerators: the key is
+/// translated from the hardware keycode, and modifiers consumed by that
+/// translation (Shift turning Tab into ISO_Left_Tab) are not compared.
+fn find_match(event: &EventKey, accelerators: &[Accelerator]) -> Option<Accelerator> {
+    let state = event.state() & gtk::accelerator_get_default_mod_mask();
+    let keycode = u32::from(event.hardware_keycode());
+    let keymap = gdk::Display::default().and_then(|display| Keymap::for_display(&display));
+    let (keyval, consumed) = keymap
+        .as_ref()
+        .and_then(|keymap| {
+            keymap.translate_keyboard_state(keycode, event.state(), i32::from(event.group()))
+        })
+        .map(|(keyval, _, _, consumed)| (keyval, consumed))
+        .unwrap_or((*event.keyval(), ModifierType::empty()));
+    let keyval = *Key::from(keyval).to_lower();
+
+    match_accelerator(keyval, state, consumed, accelerators, |key| {
+        keymap.as_ref().is_some_and(|keymap| {
+            keymap
+                .entries_for_keyval(key)
+                .iter()
+                .any(|entry| entry.keycode() == keycode && entry.level() == 0)
+        })
+    })
+}
+
+/// Picks the accelerator for a translated key press. As in GTK's key hash
+/// lookup, an exact match on key and modifiers wins; consumed modifiers are
+/// only ignored when nothing matches exactly, so Ctrl+Shift+T fires
+/// `<Control><Shift>t` rather than `<Control>t` when both are set.
+/// `unshifted_on_keycode` tells whether a key value is the unshifted key of
+/// the pressed keycode.
+fn match_accelerator(
+    keyval: u32,
+    state: ModifierType,
+    consumed: ModifierType,
+    accelerators: &[Accelerator],
+    unshifted_on_keycode: impl Fn(u32) -> bool,
+) -> Option<Accelerator> {
+    if let Some(&exact) = accelerators
+        .iter()
+        .find(|&&(key, mods)| key == keyval && mods == state)
+    {
+        return Some(exact);
+    }
+
+    accelerators.iter().copied().find(|&(key, mods)| {
+        if key == keyval && mods & !consumed == state & !consumed {
+            return true;
+        }
+        // `<Shift>Tab` names the unshifted key on the same keycode, with
+        // every modifier spelled out.
+        mods == state && unshifted_on_keycode(key)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_modifiers_win_over_consumed_ones() {
+        let t = *gdk::keys::constants::t;
+        let ctrl = ModifierType::CONTROL_MASK;
+        let shift = ModifierType::SHIFT_MASK;
+        // `<Control>t` and `<Control><Shift>t`.
+        let accelerators = [(t, ctrl), (t, ctrl | shift)];
+
+        // Shift is consumed translating t into T.
+        let matched = match_accelerator(t, ctrl | shift, shift, &accelerators, |_| false);
+        assert_eq!(matched, Some(accelerators[1]));
+
+        let matched = match_accelerator(t, ctrl, ModifierType::empty(), &accelerators, |_| false);
+        assert_eq!(matched, Some(accelerators[0]));
+    }
+
+    #[test]
+    fn consumed_modifiers_are_ignored_without_an_exact_match() {
+        let t = *gdk::keys::constants::t;
+        let ctrl = ModifierType::CONTROL_MASK;
+        let shift = ModifierType::SHIFT_MASK;
+        let accelerators = [(t, ctrl)];
+
+        let matched = match_accelerator(t, ctrl | shift, shift, &accelerators, |_| false);
+        assert_eq!(matched, Some(accelerators[0]));
+    }
+}
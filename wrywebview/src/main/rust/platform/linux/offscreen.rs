@@ -0,0 +1,48 @@
+//! WebViews hosted in unmapped `GtkOffscreenWindow`s.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use gtk::prelude::{GtkWindowExt, WidgetExt, WidgetExtManual};
+use wry::{WebViewBuilder, WebViewBuilderExtUnix, WebViewExtUnix};
+
+use crate::error::WebViewError;
+
+thread_local! {
+    // Only touched from the GTK thread, which owns the windows.
+    static WINDOWS: RefCell<HashMap<u64, gtk::OffscreenWindow>> = RefCell::new(HashMap::new());
+}
+
+/// Builds the WebView inside a new offscreen window of the given size.
+pub fn build(
+    builder: WebViewBuilder<'_>,
+    width: i32,
+    height: i32,
+) -> Result<(wry::WebView, gtk::OffscreenWindow), WebViewError> {
+    let window = gtk::OffscreenWindow::new();
+    let webview = builder.build_gtk(&window)?;
+    webview.webview().set_size_request(width.max(1), height.max(1));
+    // Offscreen windows are realized and rendered when shown, but never mapped.
+    window.show_all();
+    Ok((webview, window))
+}
+
+/// Keeps the window alive until the WebView is destroyed.
+pub fn adopt(id: u64, window: gtk::OffscreenWindow) {
+    WINDOWS.with(|windows| windows.borrow_mut().insert(id, window));
+}
+
+/// Resizes an offscreen WebView; other WebViews are left alone.
+pub fn resize(id: u64, webview: &webkit2gtk::WebView, width: i32, height: i32) {
+    let Some(window) = WINDOWS.with(|windows| windows.borrow().get(&id).cloned()) else {
+        return;
+    };
+    webview.set_size_request(width.max(1), height.max(1));
+    window.resize(width.max(1), height.max(1));
+}
+
+pub fn destroy(id: u64) {
+    if let Some(window) = WINDOWS.with(|windows| windows.borrow_mut().remove(&id)) {
+        unsafe { window.destroy() };
+    }
+}
@@ -0,0 +1,369 @@
+//! Per-profile permission policies, persisted as JSON in the profile's data
+//! directory so pre-granted or blocked permissions survive restarts.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde_json::{json, Value};
+
+use crate::error::WebViewError;
+use crate::{PermissionDecision, PermissionKind};
+
+/// Origin matching any origin without a more specific policy.
+const ANY_ORIGIN: &str = "*";
+
+const POLICY_FILE: &str = "permissions.json";
+
+type Policies = HashMap<(String, PermissionKind), PermissionDecision>;
+
+/// Policies of the profiles loaded so far, keyed by their file.
+static PROFILES: OnceLock<Mutex<HashMap<PathBuf, Policies>>> = OnceLock::new();
+
+fn profiles() -> &'static Mutex<HashMap<PathBuf, Policies>> {
+    PROFILES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Profile names become directory names, so they are restricted to a safe set.
+pub fn validate_profile(profile: &str) -> Result<(), WebViewError> {
+    let valid = !profile.is_empty()
+        && profile.len() <= 64
+        && !profile.starts_with('.')
+        && profile
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(WebViewError::InvalidProfile(profile.to_string()))
+    }
+}
+
+pub fn normalize_origin(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_ascii_lowercase()
+}
+
+fn policy_path(root: &Path, profile: &str) -> PathBuf {
+    root.join(profile).join(POLICY_FILE)
+}
+
+/// Stores a decision for the profile and writes it to disk; `AskLater` removes it.
+pub fn set(
+    root: &Path,
+    profile: &str,
+    origin: &str,
+    kind: PermissionKind,
+    decision: PermissionDecision,
+) -> Result<(), WebViewError> {
+    validate_profile(profile)?;
+    let mut profiles = profiles()
+        .lock()
+        .map_err(|_| WebViewError::Internal("permission policy lock poisoned".to_string()))?;
+    let path = policy_path(root, profile);
+    let policies = profiles.entry(path.clone()).or_insert_with(|| load(&path));
+
+    let key = (normalize_origin(origin), kind);
+    match decision {
+        PermissionDecision::AskLater => policies.remove(&key),
+        decision => policies.insert(key, decision),
+    };
+    save(&path, policies)
+}
+
+/// Looks up the stored decision for a request made by `origin` in the profile.
+pub fn decision(
+    root: &Path,
+    profile: &str,
+    origin: &str,
+    kinds: &[PermissionKind],
+) -> Option<PermissionDecision> {
+    validate_profile(profile).ok()?;
+    let mut profiles = profiles().lock().ok()?;
+    let path = policy_path(root, profile);
+    let policies = profiles.entry(path.clone()).or_insert_with(|| load(&path));
+    combine(policies, &normalize_origin(origin), kinds)
+}
+
+fn combine(
+    policies: &Policies,
+    origin: &str,
+    kinds: &[PermissionKind],
+) -> Option<PermissionDecision> {
+    if kinds.is_empty() {
+        return None;
+    }
+    let decision_for = |kind: PermissionKind| {
+        policies
+            .get(&(origin.to_string(), kind))
+            .or_else(|| policies.get(&(ANY_ORIGIN.to_string(), kind)))
+            .copied()
+    };
+
+    // A combined request (camera + microphone) needs every kind allowed.
+    let decisions: Vec<Option<PermissionDecision>> =
+        kinds.iter().map(|k| decision_for(*k)).collect();
+    if decisions.contains(&Some(PermissionDecision::Deny)) {
+        return Some(PermissionDecision::Deny);
+    }
+    if decisions
+        .iter()
+        .all(|d| *d == Some(PermissionDecision::Allow))
+    {
+        return Some(PermissionDecision::Allow);
+    }
+    None
+}
+
+/// A missing or unreadable file is an empty policy set; the next `set` rewrites it.
+fn load(path: &Path) -> Policies {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|text| decode(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save(path: &Path, policies: &Policies) -> Result<(), WebViewError> {
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Written through a temporary file so a crash never leaves half a file.
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, encode(policies))?;
+        fs::rename(&tmp, path)
+    };
+    write().map_err(|e| {
+        WebViewError::Internal(format!(
+            "saving permission policies to {} failed: {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+fn encode(policies: &Policies) -> String {
+    let mut entries: Vec<_> = policies.iter().collect();
+    entries.sort_by(|a, b| {
+        (a.0 .0.as_str(), kind_name(a.0 .1)).cmp(&(b.0 .0.as_str(), kind_name(b.0 .1)))
+    });
+    let entries: Vec<Value> = entries
+        .into_iter()
+        .map(|((origin, kind), decision)| {
+            json!({
+                "origin": origin,
+                "kind": kind_name(*kind),
+                "decision": decision_name(*decision),
+            })
+        })
+        .collect();
+    json!({ "version": 1, "policies": entries }).to_string()
+}
+
+fn decode(text: &str) -> Result<Policies, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let entries = value
+        .get("policies")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "missing policies".to_string())?;
+
+    // Entries this version does not understand are skipped, not fatal.
+    let mut policies = Policies::new();
+    for entry in entries {
+        let field = |name: &str| entry.get(name).and_then(Value::as_str);
+        let (Some(origin), Some(kind), Some(decision)) = (
+            field("origin"),
+            field("kind").and_then(kind_from_name),
+            field("decision").and_then(decision_from_name),
+        ) else {
+            continue;
+        };
+        policies.insert((normalize_origin(origin), kind), decision);
+    }
+    Ok(policies)
+}
+
+fn kind_name(kind: PermissionKind) -> &'static str {
+    match kind {
+        PermissionKind::Geolocation => "geolocation",
+        PermissionKind::Camera => "camera",
+        PermissionKind::Microphone => "microphone",
+        PermissionKind::Notifications => "notifications",
+        PermissionKind::DeviceInfo => "deviceInfo",
+        PermissionKind::MediaKeySystem => "mediaKeySystem",
+        PermissionKind::PointerLock => "pointerLock",
+        PermissionKind::WebsiteDataAccess => "websiteDataAccess",
+    }
+}
+
+fn kind_from_name(name: &str) -> Option<PermissionKind> {
+    Some(match name {
+        "geolocation" => PermissionKind::Geolocation,
+        "camera" => PermissionKind::Camera,
+        "microphone" => PermissionKind::Microphone,
+        "notifications" => PermissionKind::Notifications,
+        "deviceInfo" => PermissionKind::DeviceInfo,
+        "mediaKeySystem" => PermissionKind::MediaKeySystem,
+        "pointerLock" => PermissionKind::PointerLock,
+        "websiteDataAccess" => PermissionKind::WebsiteDataAccess,
+        _ => return None,
+    })
+}
+
+fn decision_name(decision: PermissionDecision) -> &'static str {
+    match decision {
+        PermissionDecision::Allow => "allow",
+        PermissionDecision::Deny => "deny",
+        PermissionDecision::AskLater => "askLater",
+    }
+}
+
+fn decision_from_name(name: &str) -> Option<PermissionDecision> {
+    match name {
+        "allow" => Some(PermissionDecision::Allow),
+        "deny" => Some(PermissionDecision::Deny),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policies(entries: &[(&str, PermissionKind, PermissionDecision)]) -> Policies {
+        entries
+            .iter()
+            .map(|(origin, kind, decision)| ((origin.to_string(), *kind), *decision))
+            .collect()
+    }
+
+    #[test]
+    fn normalizes_origins() {
+        assert_eq!(
+            normalize_origin(" HTTPS://Example.com/ "),
+            "https://example.com"
+        );
+        assert_eq!(normalize_origin("*"), "*");
+    }
+
+    #[test]
+    fn specific_origin_overrides_wildcard() {
+        let policies = policies(&[
+            ("*", PermissionKind::Camera, PermissionDecision::Deny),
+            (
+                "https://a.test",
+                PermissionKind::Camera,
+                PermissionDecision::Allow,
+            ),
+        ]);
+        assert_eq!(
+            combine(&policies, "https://a.test", &[PermissionKind::Camera]),
+            Some(PermissionDecision::Allow)
+        );
+        assert_eq!(
+            combine(&policies, "https://b.test", &[PermissionKind::Camera]),
+            Some(PermissionDecision::Deny)
+        );
+        assert_eq!(
+            combine(&policies, "https://b.test", &[PermissionKind::Geolocation]),
+            None
+        );
+    }
+
+    #[test]
+    fn combined_requests_need_every_kind_allowed() {
+        let policies = policies(&[(
+            "https://a.test",
+            PermissionKind::Camera,
+            PermissionDecision::Allow,
+        )]);
+        let both = [PermissionKind::Camera, PermissionKind::Microphone];
+        assert_eq!(combine(&policies, "https://a.test", &both), None);
+
+        let mut denied = policies.clone();
+        denied.insert(
+            ("https://a.test".to_string(), PermissionKind::Microphone),
+            PermissionDecision::Deny,
+        );
+        assert_eq!(
+            combine(&denied, "https://a.test", &both),
+            Some(PermissionDecision::Deny)
+        );
+        assert_eq!(combine(&denied, "https://a.test", &[]), None);
+    }
+
+    #[test]
+    fn policies_round_trip_through_json() {
+        let policies = policies(&[
+            ("*", PermissionKind::Notifications, PermissionDecision::Deny),
+            (
+                "https://a.test",
+                PermissionKind::WebsiteDataAccess,
+                PermissionDecision::Allow,
+            ),
+        ]);
+        assert_eq!(decode(&encode(&policies)).unwrap(), policies);
+    }
+
+    #[test]
+    fn decode_skips_unknown_entries() {
+        let decoded = decode(
+            r#"{"policies":[
+                {"origin":"https://A.test/","kind":"camera","decision":"allow"},
+                {"origin":"https://a.test","kind":"teleport","decision":"allow"},
+                {"origin":"https://a.test","kind":"camera","decision":"maybe"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            decoded,
+            policies(&[(
+                "https://a.test",
+                PermissionKind::Camera,
+                PermissionDecision::Allow
+            )])
+        );
+        assert!(decode("[]").is_err());
+    }
+
+    #[test]
+    fn persists_policies_per_profile() {
+        let root = std::env::temp_dir().join(format!("wrywebview-policies-{}", std::process::id()));
+        set(
+            &root,
+            "work",
+            "https://a.test",
+            PermissionKind::Geolocation,
+            PermissionDecision::Deny,
+        )
+        .unwrap();
+        let stored = load(&policy_path(&root, "work"));
+        assert_eq!(
+            combine(&stored, "https://a.test", &[PermissionKind::Geolocation]),
+            Some(PermissionDecision::Deny)
+        );
+        assert!(load(&policy_path(&root, "home")).is_empty());
+
+        // Another root with the same profile name has its own policies.
+        let other = root.join("other");
+        assert_eq!(
+            decision(
+                &other,
+                "work",
+                "https://a.test",
+                &[PermissionKind::Geolocation]
+            ),
+            None
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn rejects_unsafe_profile_names() {
+        assert!(validate_profile("default").is_ok());
+        assert!(validate_profile("team-a_2").is_ok());
+        for name in ["", "..", ".hidden", "a/b", "a\\b"] {
+            assert!(validate_profile(name).is_err(), "{name}");
+        }
+    }
+}
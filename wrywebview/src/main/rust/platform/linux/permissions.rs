@@ -0,0 +1,163 @@
+//! Permission request forwarding and stored per-profile, per-origin decisions.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use gtk::glib::{Cast, ObjectExt};
+use webkit2gtk::{
+    DeviceInfoPermissionRequest, GeolocationPermissionRequest, MediaKeySystemPermissionRequest,
+    NotificationPermissionRequest, PermissionRequest, PermissionRequestExt,
+    PointerLockPermissionRequest, SecurityOrigin, UserMediaPermissionRequest,
+    UserMediaPermissionRequestExt, WebViewExt, WebsiteDataAccessPermissionRequest,
+    WebsiteDataAccessPermissionRequestExt,
+};
+
+use super::{permission_policies, PendingRequests};
+use crate::error::WebViewError;
+use crate::log_enabled;
+use crate::state::{next_request_id, WebViewState};
+use crate::{PermissionDecision, PermissionKind, PermissionRequestInfo};
+
+thread_local! {
+    // Only touched from the GTK thread, where the signals are emitted.
+    static PENDING: PendingRequests<PermissionRequest> = PendingRequests::new();
+}
+
+/// Root of the per-profile data directories holding persisted policies.
+fn profiles_root() -> PathBuf {
+    gtk::glib::user_data_dir()
+        .join("wrywebview")
+        .join("profiles")
+}
+
+/// Stores a decision for every WebView of `profile` and persists it; `AskLater` removes it.
+pub fn set_policy(
+    profile: &str,
+    origin: &str,
+    kind: PermissionKind,
+    decision: PermissionDecision,
+) -> Result<(), WebViewError> {
+    permission_policies::set(&profiles_root(), profile, origin, kind, decision)
+}
+
+fn origin_of_uri(uri: &str) -> String {
+    permission_policies::normalize_origin(&SecurityOrigin::for_uri(uri).to_str())
+}
+
+/// The policy key of whoever asks for the permission. Storage access requests
+/// only name the requesting frame's domain, without scheme or port, so they
+/// are keyed by that domain. Other requests are attributed to the top-level
+/// origin, which must delegate them to cross-origin frames through
+/// Permissions Policy.
+fn requesting_origin(request: &PermissionRequest, top_level_origin: &str) -> String {
+    request
+        .downcast_ref::<WebsiteDataAccessPermissionRequest>()
+        .and_then(|request| request.requesting_domain())
+        .map(|domain| permission_policies::normalize_origin(&domain))
+        .unwrap_or_else(|| top_level_origin.to_string())
+}
+
+fn kinds_of(request: &PermissionRequest) -> Vec<PermissionKind> {
+    if let Some(media) = request.downcast_ref::<UserMediaPermissionRequest>() {
+        let mut kinds = Vec::new();
+        if media.is_for_video_device() {
+            kinds.push(PermissionKind::Camera);
+        }
+        if media.is_for_audio_device() {
+            kinds.push(PermissionKind::Microphone);
+        }
+        return kinds;
+    }
+    let kind = if request.is::<GeolocationPermissionRequest>() {
+        PermissionKind::Geolocation
+    } else if request.is::<NotificationPermissionRequest>() {
+        PermissionKind::Notifications
+    } else if request.is::<DeviceInfoPermissionRequest>() {
+        PermissionKind::DeviceInfo
+    } else if request.is::<MediaKeySystemPermissionRequest>() {
+        PermissionKind::MediaKeySystem
+    } else if request.is::<PointerLockPermissionRequest>() {
+        PermissionKind::PointerLock
+    } else if request.is::<WebsiteDataAccessPermissionRequest>() {
+        PermissionKind::WebsiteDataAccess
+    } else {
+        return Vec::new();
+    };
+    vec![kind]
+}
+
+fn apply(request: &PermissionRequest, allow: bool) {
+    if allow {
+        request.allow();
+    } else {
+        request.deny();
+    }
+}
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state = Arc::clone(state);
+    webview.connect_permission_request(move |webview, request| {
+        let kinds = kinds_of(request);
+        if kinds.is_empty() {
+            return false;
+        }
+        let top_level_origin = webview
+            .uri()
+            .map(|uri| origin_of_uri(&uri))
+            .unwrap_or_default();
+        let origin = requesting_origin(request, &top_level_origin);
+
+        if let Some(decision) =
+            permission_policies::decision(&profiles_root(), &state.profile, &origin, &kinds)
+        {
+            if log_enabled() {
+                eprintln!(
+                    "[wrywebview] permission_request id={} origin={} kinds={:?} policy={:?}",
+                    id, origin, kinds, decision
+                );
+            }
+            apply(request, decision == PermissionDecision::Allow);
+            return true;
+        }
+
+        let Some(handler) = state.handlers.permission.get() else {
+            return false;
+        };
+        let request_id = next_request_id();
+        PENDING.with(|pending| pending.insert(request_id, id, request.clone()));
+
+        let decision = handler.handle_permission_request(PermissionRequestInfo {
+            request_id,
+            origin,
+            top_level_origin,
+            kinds,
+        });
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] permission_request id={} request={} decision={:?}",
+                id, request_id, decision
+            );
+        }
+        if decision != PermissionDecision::AskLater {
+            if let Some(request) = PENDING.with(|pending| pending.take(id, request_id)) {
+                apply(&request, decision == PermissionDecision::Allow);
+            }
+        }
+        true
+    });
+}
+
+pub fn respond(webview_id: u64, request_id: u64, allow: bool) -> Result<(), WebViewError> {
+    let Some(request) = PENDING.with(|pending| pending.take(webview_id, request_id)) else {
+        return Err(WebViewError::RequestNotFound(request_id));
+    };
+    apply(&request, allow);
+    Ok(())
+}
+
+/// Denies the requests still pending for a WebView.
+pub fn discard_pending(webview_id: u64) {
+    for request in PENDING.with(|pending| pending.take_all(webview_id)) {
+        request.deny();
+    }
+}
@@ -0,0 +1,204 @@
+//! Printing through `WebKitPrintOperation`: PDF export and the native dialog.
+
+use std::cell::{OnceCell, RefCell};
+use std::ffi::{c_char, c_void, CStr};
+use std::path::Path;
+use std::rc::Rc;
+
+use gtk::glib::Cast;
+use gtk::prelude::WidgetExt;
+use gtk::{PageOrientation, PageSetup, PaperSize, PrintSettings, Unit};
+use webkit2gtk::{
+    PrintOperation, PrintOperationExt, PrintOperationResponse, SettingsExt, WebViewExt,
+};
+
+use crate::error::WebViewError;
+use crate::log_enabled;
+use crate::{CompletionCallback, PdfOrientation, PdfPageSize, PdfPrintOptions};
+
+/// `GtkPrinter` API from gtkunixprint, which gtk-rs does not bind.
+#[repr(C)]
+struct GtkPrinter {
+    _private: [u8; 0],
+}
+
+type GtkPrinterFunc = unsafe extern "C" fn(*mut GtkPrinter, *mut c_void) -> glib::ffi::gboolean;
+
+extern "C" {
+    fn gtk_enumerate_printers(
+        func: GtkPrinterFunc,
+        data: *mut c_void,
+        destroy: glib::ffi::GDestroyNotify,
+        wait: glib::ffi::gboolean,
+    );
+    fn gtk_printer_get_name(printer: *mut GtkPrinter) -> *const c_char;
+    fn gtk_printer_is_virtual(printer: *mut GtkPrinter) -> glib::ffi::gboolean;
+    fn gtk_printer_accepts_pdf(printer: *mut GtkPrinter) -> glib::ffi::gboolean;
+}
+
+thread_local! {
+    // Enumerating waits for every print backend; the answer does not change.
+    static FILE_PRINTER: OnceCell<Option<String>> = const { OnceCell::new() };
+}
+
+/// Name of GTK's file printer. The name is translated, so the printer is found
+/// by its traits instead: the file backend's printer is virtual and takes PDF.
+fn file_printer() -> Option<String> {
+    unsafe extern "C" fn visit(printer: *mut GtkPrinter, data: *mut c_void) -> glib::ffi::gboolean {
+        let found = &mut *(data as *mut Option<String>);
+        if gtk_printer_is_virtual(printer) != 0 && gtk_printer_accepts_pdf(printer) != 0 {
+            *found = Some(
+                CStr::from_ptr(gtk_printer_get_name(printer))
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+            return glib::ffi::GTRUE;
+        }
+        glib::ffi::GFALSE
+    }
+
+    FILE_PRINTER.with(|cell| {
+        cell.get_or_init(|| {
+            let mut found: Option<String> = None;
+            unsafe {
+                gtk_enumerate_printers(
+                    visit,
+                    &mut found as *mut Option<String> as *mut c_void,
+                    None,
+                    glib::ffi::GTRUE,
+                );
+            }
+            if log_enabled() {
+                eprintln!("[wrywebview] file printer={:?}", found);
+            }
+            found
+        })
+        .clone()
+    })
+}
+
+fn paper_size(size: &PdfPageSize) -> PaperSize {
+    let name = match size {
+        PdfPageSize::A3 => gtk::PAPER_NAME_A3,
+        PdfPageSize::A4 => gtk::PAPER_NAME_A4,
+        PdfPageSize::A5 => gtk::PAPER_NAME_A5,
+        PdfPageSize::Letter => gtk::PAPER_NAME_LETTER,
+        PdfPageSize::Legal => gtk::PAPER_NAME_LEGAL,
+        PdfPageSize::Custom {
+            width_mm,
+            height_mm,
+        } => {
+            return PaperSize::new_custom("custom", "Custom", *width_mm, *height_mm, Unit::Mm);
+        }
+    };
+    PaperSize::new(Some(name))
+}
+
+fn page_setup(options: &PdfPrintOptions) -> PageSetup {
+    let setup = PageSetup::new();
+    setup.set_paper_size(&paper_size(&options.page_size));
+    setup.set_orientation(match options.orientation {
+        PdfOrientation::Portrait => PageOrientation::Portrait,
+        PdfOrientation::Landscape => PageOrientation::Landscape,
+    });
+    setup.set_top_margin(options.margins.top_mm, Unit::Mm);
+    setup.set_right_margin(options.margins.right_mm, Unit::Mm);
+    setup.set_bottom_margin(options.margins.bottom_mm, Unit::Mm);
+    setup.set_left_margin(options.margins.left_mm, Unit::Mm);
+    setup
+}
+
+pub fn print_to_pdf(
+    webview: &webkit2gtk::WebView,
+    path: &str,
+    options: &PdfPrintOptions,
+    callback: Box<dyn CompletionCallback>,
+) -> Result<(), WebViewError> {
+    let absolute = std::path::absolute(Path::new(path))
+        .map_err(|e| WebViewError::Internal(format!("invalid pdf path {}: {}", path, e)))?;
+    let uri = gtk::glib::filename_to_uri(&absolute, None)
+        .map_err(|e| WebViewError::Internal(format!("invalid pdf path {}: {}", path, e)))?;
+
+    // WebKit draws no header or footer, and GTK's file backend cannot add them.
+    if options.header_footer {
+        return Err(WebViewError::UnsupportedOption("header_footer".to_string()));
+    }
+    let printer = file_printer().ok_or_else(|| {
+        WebViewError::Internal("GTK's print-to-file printer is unavailable".to_string())
+    })?;
+
+    let settings = PrintSettings::new();
+    settings.set_printer(&printer);
+    settings.set(gtk::PRINT_SETTINGS_OUTPUT_FILE_FORMAT, Some("pdf"));
+    settings.set(gtk::PRINT_SETTINGS_OUTPUT_URI, Some(&uri));
+
+    let operation = PrintOperation::new(webview);
+    operation.set_print_settings(&settings);
+    operation.set_page_setup(&page_setup(options));
+
+    // Backgrounds are a view setting; restore it once the job is done.
+    let web_settings = WebViewExt::settings(webview);
+    let previous_backgrounds = web_settings.as_ref().map(|s| s.is_print_backgrounds());
+    if let Some(web_settings) = &web_settings {
+        web_settings.set_print_backgrounds(options.print_backgrounds);
+    }
+
+    let error: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+    let error_for_failed = Rc::clone(&error);
+    operation.connect_failed(move |_, e| {
+        error_for_failed.replace(Some(e.to_string()));
+    });
+
+    // `finished` follows `failed`; the clone keeps the operation alive until then.
+    let keep_alive = RefCell::new(Some(operation.clone()));
+    let callback = RefCell::new(Some(callback));
+    let path = path.to_string();
+    operation.connect_finished(move |_| {
+        if let (Some(web_settings), Some(previous)) = (&web_settings, previous_backgrounds) {
+            web_settings.set_print_backgrounds(previous);
+        }
+        let failure = error.borrow_mut().take();
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] print_to_pdf finished path={} error={:?}",
+                path, failure
+            );
+        }
+        if let Some(callback) = callback.borrow_mut().take() {
+            match failure {
+                Some(message) => callback.on_error(message),
+                None => callback.on_finished(),
+            }
+        }
+        keep_alive.borrow_mut().take();
+    });
+
+    operation.print();
+    Ok(())
+}
+
+/// Opens the native print dialog, parented to the window hosting the webview.
+pub fn print_with_dialog(webview: &webkit2gtk::WebView) {
+    let operation = PrintOperation::new(webview);
+    let parent = webview
+        .toplevel()
+        .and_then(|widget| widget.downcast::<gtk::Window>().ok());
+
+    let keep_alive = Rc::new(RefCell::new(Some(operation.clone())));
+    let keep_alive_for_finished = Rc::clone(&keep_alive);
+    operation.connect_finished(move |_| {
+        keep_alive_for_finished.borrow_mut().take();
+    });
+
+    // The dialog runs a nested main loop; start it outside the calling task.
+    gtk::glib::idle_add_local_once(move || {
+        let response = operation.run_dialog(parent.as_ref());
+        if log_enabled() {
+            eprintln!("[wrywebview] print dialog response={:?}", response);
+        }
+        // Nothing is printed (and `finished` is not emitted) when cancelled.
+        if response != PrintOperationResponse::Print {
+            keep_alive.borrow_mut().take();
+        }
+    });
+}
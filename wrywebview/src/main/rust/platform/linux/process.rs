@@ -0,0 +1,79 @@
+//! Web process termination reporting and automatic recovery.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use webkit2gtk::WebViewExt;
+
+use crate::log_enabled;
+use crate::state::WebViewState;
+use crate::{WebProcessTerminationReason, WebViewEvent};
+
+/// A web process dying again this soon after a recovery is not reloaded, so a
+/// page that reliably crashes the engine cannot loop forever.
+const MIN_RECOVERY_INTERVAL: Duration = Duration::from_secs(5);
+
+thread_local! {
+    // Only touched from the GTK thread, which emits the signal.
+    static LAST_RECOVERY: RefCell<HashMap<u64, Instant>> = RefCell::new(HashMap::new());
+}
+
+pub fn connect(webview: &webkit2gtk::WebView, id: u64, state: &Arc<WebViewState>) {
+    let state = Arc::clone(state);
+    webview.connect_web_process_terminated(move |webview, reason| {
+        let reason = match reason {
+            webkit2gtk::WebProcessTerminationReason::ExceededMemoryLimit => {
+                WebProcessTerminationReason::ExceededMemoryLimit
+            }
+            webkit2gtk::WebProcessTerminationReason::TerminatedByApi => {
+                WebProcessTerminationReason::TerminatedByApi
+            }
+            _ => WebProcessTerminationReason::Crashed,
+        };
+        let url = state.current_url.lock().map(|url| url.clone()).ok();
+        let recovering =
+            state.auto_recover.load(Ordering::SeqCst) && url.is_some() && begin_recovery(id);
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] web_process_terminated id={} reason={:?} recovering={}",
+                id, reason, recovering
+            );
+        }
+
+        state.is_loading.store(false, Ordering::SeqCst);
+        state.notify_load_finished();
+        let _ = state.push_event(WebViewEvent::WebProcessTerminated { reason, recovering });
+
+        if let (true, Some(url)) = (recovering, url) {
+            state.is_loading.store(true, Ordering::SeqCst);
+            webview.load_uri(&url);
+        }
+    });
+}
+
+/// Kills the WebView's web process, as a crash would (for tests).
+pub fn terminate(webview: &webkit2gtk::WebView) {
+    webview.terminate_web_process();
+}
+
+pub fn discard(id: u64) {
+    LAST_RECOVERY.with(|last| last.borrow_mut().remove(&id));
+}
+
+fn begin_recovery(id: u64) -> bool {
+    LAST_RECOVERY.with(|last| {
+        let mut last = last.borrow_mut();
+        let now = Instant::now();
+        if last
+            .get(&id)
+            .is_some_and(|at| now.duration_since(*at) < MIN_RECOVERY_INTERVAL)
+        {
+            return false;
+        }
+        last.insert(id, now);
+        true
+    })
+}
@@ -0,0 +1,129 @@
+//! Hardware acceleration policy and the software-rendering fallback.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use gtk::prelude::{WidgetExt, WidgetExtManual};
+use webkit2gtk::{SettingsExt, WebViewExt};
+
+use crate::log_enabled;
+use crate::{HardwareAccelerationPolicy, Renderer};
+
+/// Probed once per process, on the GTK thread; the GL stack does not change
+/// at runtime.
+static GL_AVAILABLE: OnceLock<bool> = OnceLock::new();
+
+thread_local! {
+    // Renderer selected for each WebView when its policy was applied.
+    static RENDERERS: RefCell<HashMap<u64, Renderer>> = RefCell::new(HashMap::new());
+}
+
+/// Applies the requested policy, falling back to software rendering when GL
+/// cannot be initialized. `None` keeps WebKit's default unless GL is missing.
+///
+/// wry offers no access to the settings before it builds the WebView, so this
+/// must run before the first load is started instead.
+pub fn apply(webview: &webkit2gtk::WebView, id: u64, policy: Option<HardwareAccelerationPolicy>) {
+    let Some(settings) = WebViewExt::settings(webview) else {
+        RENDERERS.with(|renderers| renderers.borrow_mut().insert(id, Renderer::Software));
+        return;
+    };
+    let requested = match policy {
+        Some(HardwareAccelerationPolicy::Always) => {
+            Some(webkit2gtk::HardwareAccelerationPolicy::Always)
+        }
+        Some(HardwareAccelerationPolicy::Never) => {
+            Some(webkit2gtk::HardwareAccelerationPolicy::Never)
+        }
+        Some(HardwareAccelerationPolicy::OnDemand) => {
+            Some(webkit2gtk::HardwareAccelerationPolicy::OnDemand)
+        }
+        None => None,
+    };
+    let wants_gl = requested != Some(webkit2gtk::HardwareAccelerationPolicy::Never);
+    let effective = if wants_gl && !gl_available() {
+        if log_enabled() {
+            eprintln!(
+                "[wrywebview] GL unavailable, falling back to software rendering id={}",
+                id
+            );
+        }
+        Some(webkit2gtk::HardwareAccelerationPolicy::Never)
+    } else {
+        requested
+    };
+    if let Some(effective) = effective {
+        settings.set_hardware_acceleration_policy(effective);
+    }
+
+    // WebKit does not report whether a page is composited, so any policy
+    // that lets WebKit composite with GL, with GL working, counts as hardware
+    // rendering. `OnDemand` composites the pages that need it on the GPU.
+    let compositing = settings.hardware_acceleration_policy()
+        != webkit2gtk::HardwareAccelerationPolicy::Never
+        && gl_available()
+        && !compositing_disabled_by_env();
+    let renderer = if compositing {
+        Renderer::Hardware
+    } else {
+        Renderer::Software
+    };
+    if log_enabled() {
+        eprintln!(
+            "[wrywebview] renderer id={} policy={:?} renderer={:?}",
+            id, policy, renderer
+        );
+    }
+    RENDERERS.with(|renderers| renderers.borrow_mut().insert(id, renderer));
+}
+
+/// Reports the renderer selected when the WebView was created.
+pub fn renderer(id: u64) -> Renderer {
+    RENDERERS.with(|renderers| {
+        renderers
+            .borrow()
+            .get(&id)
+            .copied()
+            .unwrap_or(Renderer::Software)
+    })
+}
+
+pub fn discard(id: u64) {
+    RENDERERS.with(|renderers| renderers.borrow_mut().remove(&id));
+}
+
+/// WebKit never composites when this is set, whatever the policy.
+fn compositing_disabled_by_env() -> bool {
+    std::env::var_os("WEBKIT_DISABLE_COMPOSITING_MODE").is_some_and(|value| value != "0")
+}
+
+fn gl_available() -> bool {
+    *GL_AVAILABLE.get_or_init(probe_gl)
+}
+
+/// Creates and realizes a GL context on a hidden window, which is where
+/// EGL/GLX initialization fails on GPU-less machines. GDK's context is not
+/// the one WebKit creates, so this only predicts whether WebKit's will work.
+fn probe_gl() -> bool {
+    let window = gtk::Window::new(gtk::WindowType::Toplevel);
+    window.realize();
+    let result = match window.window() {
+        Some(gdk_window) => gdk_window
+            .create_gl_context()
+            .and_then(|context| context.realize())
+            .map_err(|e| e.to_string()),
+        None => Err("probe window has no GDK window".to_string()),
+    };
+    unsafe { window.destroy() };
+
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            if log_enabled() {
+                eprintln!("[wrywebview] GL probe failed: {}", e);
+            }
+            false
+        }
+    }
+}
@@ -0,0 +1,34 @@
+//! Saving pages as MHTML archives.
+
+use gtk::gio::{Cancellable, File};
+use webkit2gtk::{SaveMode, WebViewExt};
+
+use crate::log_enabled;
+use crate::CompletionCallback;
+
+/// WebKit writes the archive asynchronously through GIO, off the GTK thread.
+pub fn save_mhtml(
+    webview: &webkit2gtk::WebView,
+    path: &str,
+    callback: Box<dyn CompletionCallback>,
+) {
+    let file = File::for_path(path);
+    let path = path.to_string();
+    webview.save_to_file(
+        &file,
+        SaveMode::Mhtml,
+        None::<&Cancellable>,
+        move |result| {
+            if log_enabled() {
+                eprintln!(
+                    "[wrywebview] save_mhtml finished path={} result={:?}",
+                    path, result
+                );
+            }
+            match result {
+                Ok(()) => callback.on_finished(),
+                Err(e) => callback.on_error(e.to_string()),
+            }
+        },
+    );
+}
@@ -0,0 +1,62 @@
+//! Page snapshots encoded as PNG.
+
+use cairo::{Context, Format, ImageSurface, Surface};
+use gtk::gio::Cancellable;
+use webkit2gtk::{SnapshotOptions, WebViewExt};
+
+use crate::{SnapshotCallback, SnapshotRegion};
+
+pub fn capture(
+    webview: &webkit2gtk::WebView,
+    region: SnapshotRegion,
+    scale: f64,
+    callback: Box<dyn SnapshotCallback>,
+) {
+    let region = match region {
+        SnapshotRegion::Visible => webkit2gtk::SnapshotRegion::Visible,
+        SnapshotRegion::FullDocument => webkit2gtk::SnapshotRegion::FullDocument,
+    };
+    webview.snapshot(
+        region,
+        SnapshotOptions::NONE,
+        None::<&Cancellable>,
+        move |result| {
+            let png = result
+                .map_err(|e| e.to_string())
+                .and_then(|surface| encode_png(surface, scale));
+            match png {
+                Ok(png) => callback.on_snapshot(png),
+                Err(message) => callback.on_error(message),
+            }
+        },
+    );
+}
+
+/// Encodes an engine-rendered surface as PNG, resized by `scale`.
+pub fn encode_png(surface: Surface, scale: f64) -> Result<Vec<u8>, String> {
+    let source = ImageSurface::try_from(surface)
+        .map_err(|_| "snapshot is not an image surface".to_string())?;
+
+    let image = if (scale - 1.0).abs() < f64::EPSILON {
+        source
+    } else {
+        let width = ((source.width() as f64) * scale).round().max(1.0) as i32;
+        let height = ((source.height() as f64) * scale).round().max(1.0) as i32;
+        let scaled = ImageSurface::create(Format::ARgb32, width, height)
+            .map_err(|e| format!("cannot allocate scaled snapshot: {e}"))?;
+        let context = Context::new(&scaled).map_err(|e| e.to_string())?;
+        context.scale(scale, scale);
+        context
+            .set_source_surface(&source, 0.0, 0.0)
+            .map_err(|e| e.to_string())?;
+        context.paint().map_err(|e| e.to_string())?;
+        drop(context);
+        scaled
+    };
+
+    let mut png = Vec::new();
+    image
+        .write_to_png(&mut png)
+        .map_err(|e| format!("png encoding failed: {e}"))?;
+    Ok(png)
+}
@@ -27,7 +27,7 @@ where
 /// Handles both NSWindow (extracts contentView) and NSView objects.
 pub fn appkit_ns_view_from_handle(parent_handle: u64) -> Result<NonNull<c_void>, WebViewError> {
     let ptr = NonNull::new(parent_handle as *mut c_void)
-        .ok_or(WebViewError::InvalidWindowHandle)?;
+        .ok_or_else(|| WebViewError::InvalidWindowHandle("NSView handle is null".to_string()))?;
     let obj = unsafe { &*(ptr.as_ptr() as *mut AnyObject) };
     let class_name = obj.class().name().to_string_lossy();
     if log_enabled() {
@@ -36,13 +36,17 @@ pub fn appkit_ns_view_from_handle(parent_handle: u64) -> Result<NonNull<c_void>,
 
     let nswindow_name = unsafe { CStr::from_bytes_with_nul_unchecked(b"NSWindow\0") };
     let nsview_name = unsafe { CStr::from_bytes_with_nul_unchecked(b"NSView\0") };
-    let nswindow_cls = AnyClass::get(nswindow_name).ok_or(WebViewError::InvalidWindowHandle)?;
-    let nsview_cls = AnyClass::get(nsview_name).ok_or(WebViewError::InvalidWindowHandle)?;
+    let nswindow_cls = AnyClass::get(nswindow_name)
+        .ok_or_else(|| WebViewError::InvalidWindowHandle("NSWindow class unavailable".to_string()))?;
+    let nsview_cls = AnyClass::get(nsview_name)
+        .ok_or_else(|| WebViewError::InvalidWindowHandle("NSView class unavailable".to_string()))?;
 
     unsafe {
         if msg_send![obj, isKindOfClass: nswindow_cls] {
             let view: *mut AnyObject = msg_send![obj, contentView];
-            let view = NonNull::new(view).ok_or(WebViewError::InvalidWindowHandle)?;
+            let view = NonNull::new(view).ok_or_else(|| {
+                WebViewError::InvalidWindowHandle("NSWindow has no contentView".to_string())
+            })?;
             if log_enabled() {
                 eprintln!(
                     "[wrywebview] appkit handle is NSWindow, contentView=0x{:x}",
@@ -56,5 +60,8 @@ pub fn appkit_ns_view_from_handle(parent_handle: u64) -> Result<NonNull<c_void>,
         }
     }
 
-    Err(WebViewError::InvalidWindowHandle)
+    Err(WebViewError::InvalidWindowHandle(format!(
+        "expected an NSWindow or NSView, got {}",
+        class_name
+    )))
 }
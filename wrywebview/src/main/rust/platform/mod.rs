@@ -9,9 +9,14 @@ pub mod macos;
 #[cfg(target_os = "windows")]
 pub mod windows;
 
+use std::time::Duration;
+
 #[cfg(all(not(target_os = "macos"), not(target_os = "linux")))]
 use crate::error::WebViewError;
 
+/// How long blocking calls wait for the engine to answer.
+pub const ENGINE_REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+
 #[cfg(target_os = "macos")]
 pub use macos::run_on_main_thread;
 
@@ -0,0 +1,377 @@
+//! JavaScript injected into pages as initialization scripts.
+//!
+//! Scripts report back through `window.ipc.postMessage` with messages
+//! prefixed by [`crate::events::BRIDGE_PREFIX`]. Scripts that must not be
+//! affected by the page run in an isolated world on Linux, where they post
+//! through the `ipc` message handler registered for that world instead.
+
+/// Forwards `console.*` calls and uncaught errors to the host.
+pub const CONSOLE_CAPTURE: &str = r#"
+(function () {
+  if (window.__wrywebviewConsole) { return; }
+  Object.defineProperty(window, '__wrywebviewConsole', { value: true });
+  var ipc = window.ipc;
+  if (!ipc || typeof ipc.postMessage !== 'function') { return; }
+
+  function send(level, message, source, line) {
+    try {
+      ipc.postMessage('__wrywebview__:' + JSON.stringify({
+        type: 'console',
+        level: level,
+        message: message,
+        source: source || '',
+        line: line || 0
+      }));
+    } catch (e) {}
+  }
+
+  function format(value) {
+    if (typeof value === 'string') { return value; }
+    if (value instanceof Error) { return value.stack || String(value); }
+    try {
+      var json = JSON.stringify(value);
+      return json === undefined ? String(value) : json;
+    } catch (e) {
+      return String(value);
+    }
+  }
+
+  function callSite() {
+    var frames = String(new Error().stack || '').split('\n');
+    if (frames[0] === 'Error') { frames.shift(); }
+    // [0] callSite, [1] console wrapper, [2] the page's call.
+    var match = /(\S+?):(\d+):\d+\)?\s*$/.exec(frames[2] || '');
+    return match ? { source: match[1].replace(/^.*?[@(]/, ''), line: Number(match[2]) } : {};
+  }
+
+  ['debug', 'log', 'info', 'warn', 'error', 'trace'].forEach(function (level) {
+    var original = console[level];
+    if (typeof original !== 'function') { return; }
+    console[level] = function () {
+      var site = callSite();
+      send(level, Array.prototype.map.call(arguments, format).join(' '), site.source, site.line);
+      return original.apply(console, arguments);
+    };
+  });
+
+  window.addEventListener('error', function (event) {
+    send('error', event.message || format(event.error), event.filename, event.lineno);
+  });
+  window.addEventListener('unhandledrejection', function (event) {
+    send('error', 'Unhandled promise rejection: ' + format(event.reason), location.href, 0);
+  });
+})();
+"#;
+
+/// Reports theme-color, description, canonical and manifest links whenever
+/// they change.
+pub const METADATA_OBSERVER: &str = r#"
+(function () {
+  if (window.top !== window || window.__wrywebviewMetadata) { return; }
+  Object.defineProperty(window, '__wrywebviewMetadata', { value: true });
+  var ipc = window.ipc;
+  if (!ipc || typeof ipc.postMessage !== 'function') { return; }
+
+  function themeColor() {
+    var metas = document.querySelectorAll('meta[name="theme-color" i]');
+    for (var i = 0; i < metas.length; i++) {
+      var media = metas[i].getAttribute('media');
+      if (!media || window.matchMedia(media).matches) { return metas[i].content; }
+    }
+    return '';
+  }
+
+  function content(selector) {
+    var element = document.querySelector(selector);
+    return element ? element.content || '' : '';
+  }
+
+  function href(selector) {
+    var element = document.querySelector(selector);
+    return element ? element.href || '' : '';
+  }
+
+  var last = null;
+  function report() {
+    var json = JSON.stringify({
+      type: 'metadata',
+      themeColor: themeColor(),
+      description: content('meta[name="description" i]'),
+      canonical: href('link[rel~="canonical" i]'),
+      manifest: href('link[rel~="manifest" i]')
+    });
+    if (json === last) { return; }
+    last = json;
+    try { ipc.postMessage('__wrywebview__:' + json); } catch (e) {}
+  }
+
+  var scheduled = false;
+  function schedule() {
+    if (scheduled) { return; }
+    scheduled = true;
+    setTimeout(function () { scheduled = false; report(); }, 100);
+  }
+
+  function start() {
+    report();
+    new MutationObserver(schedule).observe(document.head || document.documentElement, {
+      childList: true,
+      subtree: true,
+      attributes: true,
+      attributeFilter: ['name', 'content', 'rel', 'href', 'media']
+    });
+  }
+
+  if (document.readyState === 'loading') {
+    document.addEventListener('DOMContentLoaded', start);
+  } else {
+    start();
+  }
+})();
+"#;
+
+/// Reports the size of the document's content whenever it changes.
+pub const CONTENT_SIZE_OBSERVER: &str = r#"
+(function () {
+  if (window.top !== window || window.__wrywebviewContentSize) { return; }
+  Object.defineProperty(window, '__wrywebviewContentSize', { value: true });
+  var ipc = window.ipc;
+  if (!ipc || typeof ipc.postMessage !== 'function' || !window.ResizeObserver) { return; }
+
+  var lastWidth = -1;
+  var lastHeight = -1;
+  // The root element is at least as large as the viewport, so the body is
+  // measured instead; otherwise the size could never shrink below it.
+  function measure() {
+    var body = document.body;
+    if (!body) {
+      var root = document.documentElement;
+      return { width: root.scrollWidth, height: root.scrollHeight };
+    }
+    var rect = body.getBoundingClientRect();
+    var style = getComputedStyle(body);
+    var margin = function (side) { return parseFloat(style['margin' + side]) || 0; };
+    return {
+      width: Math.max(rect.width, body.scrollWidth) + margin('Left') + margin('Right'),
+      height: Math.max(rect.height, body.scrollHeight) + margin('Top') + margin('Bottom')
+    };
+  }
+
+  function report() {
+    var size = measure();
+    var width = Math.ceil(size.width);
+    var height = Math.ceil(size.height);
+    if (width === lastWidth && height === lastHeight) { return; }
+    lastWidth = width;
+    lastHeight = height;
+    try {
+      ipc.postMessage('__wrywebview__:' + JSON.stringify({
+        type: 'contentSize',
+        width: width,
+        height: height
+      }));
+    } catch (e) {}
+  }
+
+  var scheduled = false;
+  function schedule() {
+    if (scheduled) { return; }
+    scheduled = true;
+    requestAnimationFrame(function () { scheduled = false; report(); });
+  }
+
+  function start() {
+    var observer = new ResizeObserver(schedule);
+    observer.observe(document.documentElement);
+    if (document.body) { observer.observe(document.body); }
+    window.addEventListener('load', schedule);
+    report();
+  }
+
+  if (document.readyState === 'loading') {
+    document.addEventListener('DOMContentLoaded', start);
+  } else {
+    start();
+  }
+})();
+"#;
+
+/// Reports the scroll position, throttled to one message per 100 ms.
+///
+/// Runs in an isolated world on Linux, so page overrides of `scrollX` and
+/// friends are not seen.
+pub const SCROLL_TRACKER: &str = r#"
+(function () {
+  if (window.top !== window) { return; }
+  var handlers = window.webkit && window.webkit.messageHandlers;
+  var target = handlers && handlers.ipc ? handlers.ipc : window.ipc;
+  if (!target || typeof target.postMessage !== 'function') { return; }
+
+  var lastX = null;
+  var lastY = null;
+  function report() {
+    var x = window.scrollX;
+    var y = window.scrollY;
+    if (x === lastX && y === lastY) { return; }
+    lastX = x;
+    lastY = y;
+    try {
+      target.postMessage('__wrywebview__:' + JSON.stringify({ type: 'scroll', x: x, y: y }));
+    } catch (e) {}
+  }
+
+  // Leading report, then one trailing report per window so the final position is never lost.
+  var timer = null;
+  window.addEventListener('scroll', function () {
+    if (timer !== null) { return; }
+    report();
+    timer = setTimeout(function () { timer = null; report(); }, 100);
+  }, { passive: true, capture: true });
+  window.addEventListener('load', report);
+})();
+"#;
+
+/// Returns the selected text; text field selections are not part of
+/// `getSelection()`, so they are read from the focused field.
+pub const SELECTED_TEXT: &str = r#"
+(function () {
+  var active = document.activeElement;
+  if (active && (active.tagName === 'TEXTAREA' || active.tagName === 'INPUT')) {
+    try {
+      if (typeof active.selectionStart === 'number') {
+        return active.value.substring(active.selectionStart, active.selectionEnd);
+      }
+    } catch (e) {}
+  }
+  var selection = window.getSelection();
+  return selection ? selection.toString() : '';
+})()
+"#;
+
+/// Serializes the current DOM, doctype included.
+pub const PAGE_SOURCE: &str = r#"
+(function () {
+  var doctype = document.doctype;
+  var prefix = doctype ? new XMLSerializer().serializeToString(doctype) + '\n' : '';
+  return prefix + document.documentElement.outerHTML;
+})()
+"#;
+
+/// Automation helpers, evaluated in the isolated world on Linux so that page
+/// scripts cannot interfere. Each takes its arguments JSON-encoded.
+pub mod automation {
+    /// Posted by [`watch`] when its selector starts matching. Pages can post it
+    /// too, which only causes an extra check.
+    pub const SELECTOR_MATCHED: &str = r#"__wrywebview__:{"type":"selectorMatched"}"#;
+
+    fn json(value: &str) -> String {
+        serde_json::Value::from(value).to_string()
+    }
+
+    /// Text content of the first match, or `null`.
+    pub fn query_text(selector: &str) -> String {
+        format!(
+            "(function (s) {{ var e = document.querySelector(s); return e ? e.textContent : null; }})({})",
+            json(selector)
+        )
+    }
+
+    /// Whether any element matches. Without a match, a MutationObserver posts
+    /// [`SELECTOR_MATCHED`] once one appears in this document; one observer is
+    /// kept per selector.
+    pub fn watch(selector: &str) -> String {
+        format!(
+            r#"(function (s, message) {{
+  if (document.querySelector(s)) {{ return true; }}
+  var watchers = window.__wrywebviewWatchers || (window.__wrywebviewWatchers = {{}});
+  if (watchers[s]) {{ return false; }}
+  var handlers = window.webkit && window.webkit.messageHandlers;
+  var target = handlers && handlers.ipc ? handlers.ipc : window.ipc;
+  watchers[s] = new MutationObserver(function () {{
+    if (!document.querySelector(s)) {{ return; }}
+    watchers[s].disconnect();
+    delete watchers[s];
+    try {{ target.postMessage(message); }} catch (e) {{}}
+  }});
+  watchers[s].observe(document, {{ childList: true, subtree: true, attributes: true }});
+  return false;
+}})({}, {})"#,
+            json(selector),
+            json(SELECTOR_MATCHED)
+        )
+    }
+
+    /// Scrolls the first match into view and returns its center in viewport
+    /// coordinates, or `null`.
+    pub fn element_center(selector: &str) -> String {
+        format!(
+            r#"(function (s) {{
+  var e = document.querySelector(s);
+  if (!e) {{ return null; }}
+  e.scrollIntoView({{ behavior: 'instant', block: 'center', inline: 'center' }});
+  var r = e.getBoundingClientRect();
+  return {{ x: r.left + r.width / 2, y: r.top + r.height / 2 }};
+}})({})"#,
+            json(selector)
+        )
+    }
+
+    /// Focuses the first match and replaces its value (or text, for editable
+    /// content) the way typing would, firing `input` and `change`. Returns
+    /// whether an element matched.
+    pub fn fill(selector: &str, value: &str) -> String {
+        format!(
+            r#"(function (s, v) {{
+  var e = document.querySelector(s);
+  if (!e) {{ return false; }}
+  e.focus();
+  var proto = e instanceof HTMLTextAreaElement ? HTMLTextAreaElement.prototype
+    : e instanceof HTMLInputElement ? HTMLInputElement.prototype
+    : e instanceof HTMLSelectElement ? HTMLSelectElement.prototype : null;
+  if (proto) {{
+    Object.getOwnPropertyDescriptor(proto, 'value').set.call(e, v);
+  }} else if (e.isContentEditable) {{
+    e.textContent = v;
+  }} else {{
+    throw new Error('element is not editable');
+  }}
+  e.dispatchEvent(new InputEvent('input', {{ bubbles: true, inputType: 'insertReplacementText', data: v }}));
+  e.dispatchEvent(new Event('change', {{ bubbles: true }}));
+  return true;
+}})({}, {})"#,
+            json(selector),
+            json(value)
+        )
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn arguments_are_json_string_literals() {
+            assert_eq!(json(r#"a"b\c"#), r#""a\"b\\c""#);
+            assert_eq!(json("line\n\u{0}"), r#""line\n\u0000""#);
+            assert_eq!(
+                watched_selector(&watch(r#"input[name="q"]"#)),
+                r#""input[name=\"q\"]""#
+            );
+        }
+
+        #[test]
+        fn arguments_cannot_break_out_of_the_script() {
+            let script = query_text("'); alert(1); ('");
+            assert!(script.ends_with(r#"})("'); alert(1); ('")"#));
+
+            let script = fill("#a", "\"); alert(1); (\"");
+            assert!(script.ends_with(r##"})("#a", "\"); alert(1); (\"")"##));
+        }
+
+        /// The selector passed to the function expression of `watch`.
+        fn watched_selector(script: &str) -> &str {
+            let start = script.rfind("})(").unwrap() + 3;
+            let end = script.rfind(", ").unwrap();
+            &script[start..end]
+        }
+    }
+}
@@ -3,36 +3,135 @@
 use std::collections::VecDeque;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
 use std::thread::ThreadId;
+use std::time::{Duration, Instant};
 
 use wry::WebView;
 
 use crate::error::WebViewError;
+use crate::events::{ContentSize, PageMetadata, ScrollPosition, WebViewEvent};
+use crate::{
+    ContextMenuHandler, DragDropHandler, FileChooserHandler, FullscreenHandler, KeyEventHandler,
+    PermissionHandler, ScriptDialogHandler,
+};
+
+/// Profile of WebViews created without one.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Maximum number of undrained events kept per WebView, see [`eviction_rank`].
+const MAX_PENDING_EVENTS: usize = 1024;
+
+/// Order in which queued events are dropped when the queue is full, lowest
+/// first; `None` for one-shot events, which are never dropped.
+fn eviction_rank(event: &WebViewEvent) -> Option<u8> {
+    match event {
+        // State updates whose latest value can be queried, most of them
+        // frequent (hover, scroll, resize).
+        WebViewEvent::HoveredTargetChanged { .. }
+        | WebViewEvent::ScrollChanged { .. }
+        | WebViewEvent::ContentSizeChanged { .. }
+        | WebViewEvent::PageMetadataChanged { .. }
+        | WebViewEvent::FaviconChanged
+        | WebViewEvent::FocusChanged { .. } => Some(0),
+        WebViewEvent::ConsoleMessage { .. } => Some(1),
+        _ => None,
+    }
+}
+
+/// A host callback that can be replaced after the WebView is created.
+pub struct HandlerSlot<T: ?Sized>(Mutex<Option<Arc<T>>>);
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+impl<T: ?Sized> HandlerSlot<T> {
+    pub fn set(&self, handler: Option<Arc<T>>) -> Result<(), WebViewError> {
+        let mut slot = self
+            .0
+            .lock()
+            .map_err(|_| WebViewError::Internal("handler lock poisoned".to_string()))?;
+        *slot = handler;
+        Ok(())
+    }
+
+    /// Returns the current handler; the lock is released before it is invoked.
+    pub fn get(&self) -> Option<Arc<T>> {
+        self.0.lock().ok().and_then(|slot| slot.clone())
+    }
+}
+
+impl<T: ?Sized> Default for HandlerSlot<T> {
+    fn default() -> Self {
+        Self(Mutex::new(None))
+    }
+}
+
+/// Host callbacks consulted by the engine signal handlers.
+#[derive(Default)]
+pub struct WebViewHandlers {
+    pub script_dialog: HandlerSlot<dyn ScriptDialogHandler>,
+    pub permission: HandlerSlot<dyn PermissionHandler>,
+    pub file_chooser: HandlerSlot<dyn FileChooserHandler>,
+    pub fullscreen: HandlerSlot<dyn FullscreenHandler>,
+    pub context_menu: HandlerSlot<dyn ContextMenuHandler>,
+    pub drag_drop: HandlerSlot<dyn DragDropHandler>,
+    pub key_event: HandlerSlot<dyn KeyEventHandler>,
+}
 
 /// Tracks the loading state and current URL of a WebView.
 pub struct WebViewState {
+    /// Names the profile whose persisted settings (permission policies) apply.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub profile: String,
     pub is_loading: AtomicBool,
+    pub is_visible: AtomicBool,
+    /// Counts finished loads and watched DOM changes, see [`Self::wait_for_page_change`].
+    page_changes: Mutex<u64>,
+    page_changed: Condvar,
     pub current_url: Mutex<String>,
     pub page_title: Mutex<String>,
+    pub page_metadata: Mutex<PageMetadata>,
+    pub content_size: Mutex<ContentSize>,
+    pub scroll_position: Mutex<ScrollPosition>,
     history: Mutex<Vec<String>>,
     history_index: Mutex<isize>,
     ipc_messages: Mutex<VecDeque<String>>,
+    events: Mutex<VecDeque<WebViewEvent>>,
+    /// Reload the last URL when the web process terminates.
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub auto_recover: AtomicBool,
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    pub handlers: WebViewHandlers,
 }
 
 impl WebViewState {
     /// Creates a new WebViewState with the given initial URL.
     pub fn new(url: String) -> Self {
         Self {
+            profile: DEFAULT_PROFILE.to_string(),
             is_loading: AtomicBool::new(true),
+            is_visible: AtomicBool::new(true),
+            page_changes: Mutex::new(0),
+            page_changed: Condvar::new(),
             current_url: Mutex::new(url),
             page_title: Mutex::new(String::new()),
+            page_metadata: Mutex::new(PageMetadata::default()),
+            content_size: Mutex::new(ContentSize::default()),
+            scroll_position: Mutex::new(ScrollPosition::default()),
             history: Mutex::new(Vec::new()),
             history_index: Mutex::new(-1),
             ipc_messages: Mutex::new(VecDeque::new()),
+            events: Mutex::new(VecDeque::new()),
+            auto_recover: AtomicBool::new(false),
+            handlers: WebViewHandlers::default(),
         }
     }
 
+    /// Assigns the WebView to a profile other than the default one.
+    pub fn with_profile(mut self, profile: String) -> Self {
+        self.profile = profile;
+        self
+    }
+
     pub fn update_current_url(&self, url: String) -> Result<(), WebViewError> {
         {
             let mut current = self
@@ -53,6 +152,93 @@ impl WebViewState {
         Ok(())
     }
 
+    pub fn update_page_metadata(&self, metadata: PageMetadata) -> Result<(), WebViewError> {
+        let mut page_metadata = self
+            .page_metadata
+            .lock()
+            .map_err(|_| WebViewError::Internal("metadata lock poisoned".to_string()))?;
+        *page_metadata = metadata;
+        Ok(())
+    }
+
+    pub fn update_content_size(&self, size: ContentSize) -> Result<(), WebViewError> {
+        let mut content_size = self
+            .content_size
+            .lock()
+            .map_err(|_| WebViewError::Internal("content size lock poisoned".to_string()))?;
+        *content_size = size;
+        Ok(())
+    }
+
+    pub fn update_scroll_position(&self, position: ScrollPosition) -> Result<(), WebViewError> {
+        let mut scroll_position = self
+            .scroll_position
+            .lock()
+            .map_err(|_| WebViewError::Internal("scroll position lock poisoned".to_string()))?;
+        *scroll_position = position;
+        Ok(())
+    }
+
+    /// Wakes threads blocked in [`Self::wait_until_loaded`].
+    pub fn notify_load_finished(&self) {
+        self.notify_page_changed();
+    }
+
+    /// Records a page change and wakes the threads waiting for one.
+    pub fn notify_page_changed(&self) {
+        if let Ok(mut changes) = self.page_changes.lock() {
+            *changes += 1;
+        }
+        self.page_changed.notify_all();
+    }
+
+    /// Number of page changes so far, to pass to [`Self::wait_for_page_change`].
+    pub fn page_changes(&self) -> Result<u64, WebViewError> {
+        self.page_changes
+            .lock()
+            .map(|changes| *changes)
+            .map_err(|_| WebViewError::Internal("page change lock poisoned".to_string()))
+    }
+
+    /// Blocks until the page changed since `seen` was read from
+    /// [`Self::page_changes`]; returns false on timeout.
+    pub fn wait_for_page_change(&self, seen: u64, timeout: Duration) -> Result<bool, WebViewError> {
+        let guard = self
+            .page_changes
+            .lock()
+            .map_err(|_| WebViewError::Internal("page change lock poisoned".to_string()))?;
+        let (_guard, result) = self
+            .page_changed
+            .wait_timeout_while(guard, timeout, |changes| *changes == seen)
+            .map_err(|_| WebViewError::Internal("page change lock poisoned".to_string()))?;
+        Ok(!result.timed_out())
+    }
+
+    /// Blocks until the current load finishes; returns false on timeout.
+    pub fn wait_until_loaded(&self, timeout: Duration) -> Result<bool, WebViewError> {
+        // Loads can also end without a notification (e.g. `stop_loading`), so
+        // the flag is re-checked periodically.
+        const RECHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+        let deadline = Instant::now() + timeout;
+        let mut guard = self
+            .page_changes
+            .lock()
+            .map_err(|_| WebViewError::Internal("page change lock poisoned".to_string()))?;
+        while self.is_loading.load(Ordering::SeqCst) {
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(false);
+            }
+            guard = self
+                .page_changed
+                .wait_timeout(guard, (deadline - now).min(RECHECK_INTERVAL))
+                .map_err(|_| WebViewError::Internal("page change lock poisoned".to_string()))?
+                .0;
+        }
+        Ok(true)
+    }
+
     pub fn push_ipc_message(&self, message: String) -> Result<(), WebViewError> {
         let mut queue = self
             .ipc_messages
@@ -70,6 +256,38 @@ impl WebViewState {
         Ok(queue.drain(..).collect())
     }
 
+    pub fn push_event(&self, event: WebViewEvent) -> Result<(), WebViewError> {
+        let mut queue = self
+            .events
+            .lock()
+            .map_err(|_| WebViewError::Internal("event queue lock poisoned".to_string()))?;
+        if queue.len() >= MAX_PENDING_EVENTS {
+            let evict = [0, 1]
+                .into_iter()
+                .find_map(|rank| queue.iter().position(|e| eviction_rank(e) == Some(rank)));
+            match evict {
+                Some(index) => {
+                    queue.remove(index);
+                }
+                // Only one-shot events are queued. They come from the engine
+                // and the host, not from page scripts, so they may exceed the
+                // cap; anything else is dropped instead.
+                None if eviction_rank(&event).is_some() => return Ok(()),
+                None => {}
+            }
+        }
+        queue.push_back(event);
+        Ok(())
+    }
+
+    pub fn drain_events(&self) -> Result<Vec<WebViewEvent>, WebViewError> {
+        let mut queue = self
+            .events
+            .lock()
+            .map_err(|_| WebViewError::Internal("event queue lock poisoned".to_string()))?;
+        Ok(queue.drain(..).collect())
+    }
+
     pub fn can_go_back(&self) -> Result<bool, WebViewError> {
         let history = self
             .history
@@ -159,6 +377,7 @@ unsafe impl Send for WebViewEntry {}
 unsafe impl Sync for WebViewEntry {}
 
 static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
 static WEBVIEWS: OnceLock<Mutex<HashMap<u64, WebViewEntry>>> = OnceLock::new();
 
 /// Returns the global WebView registry.
@@ -171,6 +390,12 @@ pub fn next_id() -> u64 {
     NEXT_ID.fetch_add(1, Ordering::Relaxed)
 }
 
+/// Generates a new unique ID for a request awaiting a host response.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn next_request_id() -> u64 {
+    NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed)
+}
+
 /// Executes a closure with access to the WebView, ensuring thread safety.
 pub fn with_webview<F, R>(id: u64, f: F) -> Result<R, WebViewError>
 where
@@ -192,6 +417,23 @@ where
     f(webview)
 }
 
+/// Fails if called on the thread owning the WebView: its event loop delivers
+/// the engine replies that blocking calls wait for.
+#[cfg_attr(target_os = "linux", allow(dead_code))]
+pub fn ensure_off_owner_thread(id: u64, operation: &str) -> Result<(), WebViewError> {
+    let map = webviews()
+        .lock()
+        .map_err(|_| WebViewError::Internal("webview registry lock poisoned".to_string()))?;
+    let entry = map.get(&id).ok_or(WebViewError::WebViewNotFound(id))?;
+    if entry.thread_id == std::thread::current().id() {
+        return Err(WebViewError::Internal(format!(
+            "{} cannot block the thread owning the WebView",
+            operation
+        )));
+    }
+    Ok(())
+}
+
 /// Retrieves the state for a WebView by ID.
 pub fn get_state(id: u64) -> Result<Arc<WebViewState>, WebViewError> {
     let map = webviews()
@@ -243,3 +485,144 @@ pub fn unregister(id: u64) -> Result<(), WebViewError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{ConsoleMessageLevel, FocusDirection};
+
+    fn console(message: &str) -> WebViewEvent {
+        WebViewEvent::ConsoleMessage {
+            level: ConsoleMessageLevel::Error,
+            message: message.to_string(),
+            source_url: String::new(),
+            line: 0,
+        }
+    }
+
+    fn scroll(y: f64) -> WebViewEvent {
+        WebViewEvent::ScrollChanged {
+            position: ScrollPosition { x: 0.0, y },
+        }
+    }
+
+    #[test]
+    fn event_queue_is_capped() {
+        let state = WebViewState::new(String::new());
+        for i in 0..MAX_PENDING_EVENTS + 10 {
+            state.push_event(scroll(i as f64)).unwrap();
+        }
+        let events = state.drain_events().unwrap();
+        assert_eq!(events.len(), MAX_PENDING_EVENTS);
+        assert!(matches!(
+            events[0],
+            WebViewEvent::ScrollChanged { position } if position.y == 10.0
+        ));
+        assert!(state.drain_events().unwrap().is_empty());
+    }
+
+    #[test]
+    fn console_messages_survive_other_events() {
+        let state = WebViewState::new(String::new());
+        state.push_event(console("first")).unwrap();
+        for i in 0..MAX_PENDING_EVENTS * 2 {
+            state.push_event(scroll(i as f64)).unwrap();
+        }
+        let events = state.drain_events().unwrap();
+        assert_eq!(events.len(), MAX_PENDING_EVENTS);
+        assert!(matches!(
+            &events[0],
+            WebViewEvent::ConsoleMessage { message, .. } if message == "first"
+        ));
+    }
+
+    #[test]
+    fn console_messages_evict_each_other_when_full() {
+        let state = WebViewState::new(String::new());
+        for i in 0..MAX_PENDING_EVENTS + 1 {
+            state.push_event(console(&i.to_string())).unwrap();
+        }
+        let events = state.drain_events().unwrap();
+        assert_eq!(events.len(), MAX_PENDING_EVENTS);
+        assert!(matches!(
+            &events[0],
+            WebViewEvent::ConsoleMessage { message, .. } if message == "1"
+        ));
+    }
+
+    #[test]
+    fn wait_until_loaded_returns_once_the_load_finishes() {
+        let state = Arc::new(WebViewState::new(String::new()));
+        assert!(!state.wait_until_loaded(Duration::from_millis(10)).unwrap());
+
+        let loader = Arc::clone(&state);
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            loader.is_loading.store(false, Ordering::SeqCst);
+            loader.notify_load_finished();
+        });
+        assert!(state.wait_until_loaded(Duration::from_secs(5)).unwrap());
+        handle.join().unwrap();
+        // Nothing is loading any more, so it returns at once.
+        assert!(state.wait_until_loaded(Duration::ZERO).unwrap());
+    }
+
+    #[test]
+    fn page_changes_wake_waiters() {
+        let state = Arc::new(WebViewState::new(String::new()));
+        let seen = state.page_changes().unwrap();
+        assert!(!state
+            .wait_for_page_change(seen, Duration::from_millis(10))
+            .unwrap());
+
+        let notifier = Arc::clone(&state);
+        let handle = std::thread::spawn(move || notifier.notify_page_changed());
+        assert!(state
+            .wait_for_page_change(seen, Duration::from_secs(5))
+            .unwrap());
+        handle.join().unwrap();
+        // A change made before waiting is not missed.
+        assert!(state.wait_for_page_change(seen, Duration::ZERO).unwrap());
+    }
+
+    #[test]
+    fn one_shot_events_are_never_evicted() {
+        let state = WebViewState::new(String::new());
+        state
+            .push_event(WebViewEvent::FocusReleased {
+                direction: FocusDirection::Forward,
+            })
+            .unwrap();
+        for i in 0..MAX_PENDING_EVENTS {
+            state.push_event(console(&i.to_string())).unwrap();
+            state.push_event(scroll(i as f64)).unwrap();
+        }
+        let events = state.drain_events().unwrap();
+        assert_eq!(events.len(), MAX_PENDING_EVENTS);
+        assert!(matches!(events[0], WebViewEvent::FocusReleased { .. }));
+        // Scroll updates went first; only the one pushed last is left.
+        let scrolls = events
+            .iter()
+            .filter(|e| matches!(e, WebViewEvent::ScrollChanged { .. }))
+            .count();
+        assert_eq!(scrolls, 1);
+    }
+
+    #[test]
+    fn full_queue_of_one_shot_events_keeps_them_all() {
+        let state = WebViewState::new(String::new());
+        for i in 0..MAX_PENDING_EVENTS + 1 {
+            state
+                .push_event(WebViewEvent::ContextMenuItemActivated {
+                    item_id: i.to_string(),
+                })
+                .unwrap();
+        }
+        state.push_event(scroll(1.0)).unwrap();
+        let events = state.drain_events().unwrap();
+        assert_eq!(events.len(), MAX_PENDING_EVENTS + 1);
+        assert!(events
+            .iter()
+            .all(|e| matches!(e, WebViewEvent::ContextMenuItemActivated { .. })));
+    }
+}